@@ -0,0 +1,217 @@
+use tauri::{State, command};
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use crate::models::baku_mutu::{BakuMutuClass, BakuMutuSet, BatasParameter};
+use crate::services;
+
+// Helper: Tulis seluruh baris parameter milik satu set (dipakai create, clone, update)
+async fn insert_parameter(
+    tx: &mut Transaction<'_, Sqlite>,
+    set_id: i64,
+    parameter: &[BatasParameter]
+) -> Result<(), String> {
+    let sql = "
+        INSERT INTO baku_mutu (set_id, parameter, jenis, batas_bawah, batas_atas, satuan, rujukan)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+    ";
+
+    for batas in parameter {
+        sqlx::query(sql)
+            .bind(set_id)
+            .bind(&batas.parameter)
+            .bind(batas.jenis)
+            .bind(batas.batas_bawah)
+            .bind(batas.batas_atas)
+            .bind(&batas.satuan)
+            .bind(&batas.rujukan)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| format!("Gagal menyimpan parameter {}: {}", batas.parameter, e))?;
+    }
+
+    Ok(())
+}
+
+// Helper: Jumlah sampel yang merujuk set (dihitung di dalam transaksi pengubah/penghapus set)
+async fn jumlah_sampel(tx: &mut Transaction<'_, Sqlite>, set_id: i64) -> Result<i64, String> {
+    sqlx::query_scalar("SELECT COUNT(*) FROM kualitas_air WHERE baku_mutu_id = ?")
+        .bind(set_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| format!("Gagal memeriksa pemakaian baku mutu: {}", e))
+}
+
+// Helper: Simpan set baru + parameternya dalam satu transaksi, kembalikan ID set
+async fn insert_set(pool: &SqlitePool, data: &BakuMutuSet) -> Result<i64, String> {
+    services::baku_mutu::validate(data)?;
+
+    let mut tx = pool.begin().await
+        .map_err(|e| format!("Gagal memulai transaksi: {}", e))?;
+
+    let set_id = sqlx::query("INSERT INTO baku_mutu_set (nama, rujukan, keterangan) VALUES ($1, $2, $3)")
+        .bind(data.nama.trim())
+        .bind(&data.rujukan)
+        .bind(&data.keterangan)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Gagal menyimpan baku mutu: {}", e))?
+        .last_insert_rowid();
+
+    insert_parameter(&mut tx, set_id, &data.parameter).await?;
+
+    tx.commit().await
+        .map_err(|e| format!("Gagal menyimpan baku mutu: {}", e))?;
+
+    Ok(set_id)
+}
+
+// --- COMMAND 1: DAFTAR SEMUA SET BAKU MUTU KUSTOM ---
+#[command]
+pub async fn get_all_baku_mutu(pool: State<'_, SqlitePool>) -> Result<Vec<BakuMutuSet>, String> {
+    let ids: Vec<i64> = sqlx::query_scalar("SELECT id FROM baku_mutu_set ORDER BY nama")
+        .fetch_all(pool.inner())
+        .await
+        .map_err(|e| format!("Gagal mengambil data: {}", e))?;
+
+    let mut sets = Vec::with_capacity(ids.len());
+    for id in ids {
+        sets.push(services::baku_mutu::load_set(pool.inner(), id).await?);
+    }
+
+    Ok(sets)
+}
+
+// --- COMMAND 2: TABEL KELAS BAWAAN (PP 22/2021) ---
+#[command]
+pub async fn get_baku_mutu_kelas(kelas: BakuMutuClass) -> Result<Vec<BatasParameter>, String> {
+    Ok(services::baku_mutu::standar_kelas(kelas))
+}
+
+// --- COMMAND 3: BUAT SET BARU ---
+#[command]
+pub async fn create_baku_mutu(
+    pool: State<'_, SqlitePool>,
+    data: BakuMutuSet
+) -> Result<i64, String> {
+    let id = insert_set(pool.inner(), &data).await?;
+    println!("✅ [RUST] Baku mutu '{}' tersimpan (ID: {})", data.nama, id);
+    Ok(id)
+}
+
+// --- COMMAND 4: CLONE DARI SET LAIN ATAU KELAS BAWAAN ---
+#[command]
+pub async fn clone_baku_mutu(
+    pool: State<'_, SqlitePool>,
+    sumber_id: Option<i64>,
+    kelas: Option<BakuMutuClass>,
+    nama: String
+) -> Result<i64, String> {
+    let data = match (sumber_id, kelas) {
+        (Some(id), _) => {
+            let sumber = services::baku_mutu::load_set(pool.inner(), id).await?;
+            BakuMutuSet { id: None, nama, created_at: None, ..sumber }
+        },
+        (None, Some(kelas)) => BakuMutuSet {
+            id: None,
+            nama,
+            rujukan: Some(format!("PP 22/2021 Lampiran VI ({})", kelas.label())),
+            keterangan: None,
+            parameter: services::baku_mutu::standar_kelas(kelas),
+            created_at: None,
+        },
+        (None, None) => return Err("Sumber baku mutu belum dipilih".to_string()),
+    };
+
+    insert_set(pool.inner(), &data).await
+}
+
+// --- COMMAND 5: EDIT SET (GANTI SELURUH PARAMETER) ---
+#[command]
+pub async fn update_baku_mutu(
+    pool: State<'_, SqlitePool>,
+    id: i64,
+    data: BakuMutuSet
+) -> Result<String, String> {
+    services::baku_mutu::validate(&data)?;
+
+    let mut tx = pool.begin().await
+        .map_err(|e| format!("Gagal memulai transaksi: {}", e))?;
+
+    let result = sqlx::query("UPDATE baku_mutu_set SET nama = $1, rujukan = $2, keterangan = $3 WHERE id = $4")
+        .bind(data.nama.trim())
+        .bind(&data.rujukan)
+        .bind(&data.keterangan)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Gagal mengubah baku mutu: {}", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err(format!("Baku mutu dengan ID {} tidak ditemukan", id));
+    }
+
+    // IP sampel tersimpan dihitung dengan batas lama: set yang dipakai hanya boleh diganti nama/keterangannya
+    let dipakai = jumlah_sampel(&mut tx, id).await?;
+    if dipakai > 0 {
+        let lama = sqlx::query_as::<_, BatasParameter>("SELECT * FROM baku_mutu WHERE set_id = ? ORDER BY id")
+            .bind(id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| format!("Gagal mengambil parameter baku mutu: {}", e))?;
+        if lama != data.parameter {
+            return Err(format!(
+                "Batas baku mutu ini dipakai oleh {} data sampel dan tidak bisa diubah. Buat salinan set ini (clone) lalu ubah salinannya",
+                dipakai
+            ));
+        }
+    }
+
+    sqlx::query("DELETE FROM baku_mutu WHERE set_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Gagal mengubah baku mutu: {}", e))?;
+
+    insert_parameter(&mut tx, id, &data.parameter).await?;
+
+    tx.commit().await
+        .map_err(|e| format!("Gagal mengubah baku mutu: {}", e))?;
+
+    Ok(format!("Baku mutu berhasil diubah (ID: {})", id))
+}
+
+// --- COMMAND 6: HAPUS SET ---
+#[command]
+pub async fn delete_baku_mutu(
+    pool: State<'_, SqlitePool>,
+    id: i64
+) -> Result<String, String> {
+    let mut tx = pool.begin().await
+        .map_err(|e| format!("Gagal memulai transaksi: {}", e))?;
+
+    // Set yang masih dirujuk sampel tidak boleh dihapus (IP/STORET/laporan sampel itu akan gagal)
+    let dipakai = jumlah_sampel(&mut tx, id).await?;
+    if dipakai > 0 {
+        return Err(format!("Baku mutu masih dipakai oleh {} data sampel dan tidak bisa dihapus", dipakai));
+    }
+
+    sqlx::query("DELETE FROM baku_mutu WHERE set_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Gagal menghapus data: {}", e))?;
+
+    let result = sqlx::query("DELETE FROM baku_mutu_set WHERE id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Gagal menghapus data: {}", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err(format!("Baku mutu dengan ID {} tidak ditemukan", id));
+    }
+
+    tx.commit().await
+        .map_err(|e| format!("Gagal menghapus data: {}", e))?;
+
+    Ok(format!("Baku mutu berhasil dihapus (ID: {})", id))
+}
@@ -0,0 +1,112 @@
+use tauri::{State, command, AppHandle};
+use sqlx::SqlitePool;
+use crate::commands::kualitas_air::simpan_batch;
+use crate::models::ekspor_csv::KolomEkspor;
+use crate::models::impor_tabel::{DaftarPemetaan, FileImpor, HasilImporTabel, PemetaanImpor};
+use crate::services;
+use tauri_plugin_dialog::DialogExt;
+
+// Helper: Baca file lalu validasi seluruh baris + tandai data yang sudah ada di database
+async fn validasi_file(
+    pool: &SqlitePool,
+    path: &str,
+    pemetaan: &PemetaanImpor
+) -> Result<HasilImporTabel, String> {
+    let tabel = services::impor_tabel::baca(path, pemetaan)?;
+    let mut hasil = services::impor_tabel::validasi(&tabel, pemetaan)?;
+    services::impor_tabel::tandai_duplikat(pool, &mut hasil).await?;
+    Ok(hasil)
+}
+
+// --- COMMAND 1: PILIH FILE CSV / EXCEL ---
+#[command]
+pub async fn pilih_file_impor(app: AppHandle) -> Result<String, String> {
+    let file_path = app.dialog()
+        .file()
+        .add_filter("CSV / Excel", &["csv", "txt", "xlsx", "xlsm", "xls", "ods"])
+        .blocking_pick_file();
+
+    match file_path {
+        Some(path) => Ok(path.to_string()),
+        None => Err("Import dibatalkan pengguna".to_string()),
+    }
+}
+
+// --- COMMAND 2: BACA JUDUL KOLOM & CONTOH DATA ---
+// Dipanggil ulang setiap kali sheet / baris header / pemisah diganti di form pemetaan.
+#[command]
+pub async fn baca_file_impor(path: String, pemetaan: PemetaanImpor) -> Result<FileImpor, String> {
+    let tabel = services::impor_tabel::baca(&path, &pemetaan)?;
+    Ok(services::impor_tabel::ringkas(&path, &tabel))
+}
+
+// --- COMMAND 3: DAFTAR FIELD TUJUAN PEMETAAN ---
+#[command]
+pub fn get_field_impor() -> Vec<KolomEkspor> {
+    services::impor_tabel::daftar_field()
+}
+
+// --- COMMAND 4: PRATINJAU HASIL IMPOR (TANPA MENYIMPAN) ---
+#[command]
+pub async fn pratinjau_impor(
+    pool: State<'_, SqlitePool>,
+    path: String,
+    pemetaan: PemetaanImpor
+) -> Result<HasilImporTabel, String> {
+    validasi_file(pool.inner(), &path, &pemetaan).await
+}
+
+// --- COMMAND 5: SIMPAN HASIL IMPOR ---
+// File dibaca & divalidasi ulang di backend. Baris yang error (dan duplikat, jika dipilih) dilewati;
+// sisanya disimpan dalam satu transaksi: jika satu baris gagal disimpan, tidak ada yang tersimpan.
+#[command]
+pub async fn simpan_impor(
+    pool: State<'_, SqlitePool>,
+    path: String,
+    pemetaan: PemetaanImpor,
+    lewati_duplikat: bool
+) -> Result<HasilImporTabel, String> {
+    let mut hasil = validasi_file(pool.inner(), &path, &pemetaan).await?;
+
+    let (baris, records): (Vec<usize>, Vec<_>) = hasil.baris.iter()
+        .filter(|b| !(lewati_duplikat && b.duplikat))
+        .filter_map(|b| Some((b.baris, b.record.clone()?)))
+        .unzip();
+    if records.is_empty() {
+        return Err("Tidak ada baris valid untuk disimpan".to_string());
+    }
+
+    hasil.ids = simpan_batch(pool.inner(), records).await
+        .map_err(|(idx, e)| match idx {
+            Some(idx) => format!("Baris {}: {} (tidak ada data yang tersimpan)", baris[idx], e),
+            None => e,
+        })?;
+
+    Ok(hasil)
+}
+
+// --- COMMAND 6: DAFTAR PEMETAAN TERSIMPAN ---
+#[command]
+pub async fn get_all_pemetaan_impor(app: AppHandle) -> Result<DaftarPemetaan, String> {
+    services::impor_tabel::load_all_pemetaan(&app)
+}
+
+// --- COMMAND 7: SIMPAN PEMETAAN (BUAT BARU / TIMPA) ---
+#[command]
+pub async fn save_pemetaan_impor(
+    app: AppHandle,
+    data: PemetaanImpor
+) -> Result<String, String> {
+    let path = services::impor_tabel::save_pemetaan(&app, &data)?;
+    Ok(format!("Pemetaan berhasil disimpan ke: {}", path.display()))
+}
+
+// --- COMMAND 8: HAPUS PEMETAAN ---
+#[command]
+pub async fn delete_pemetaan_impor(
+    app: AppHandle,
+    nama: String
+) -> Result<String, String> {
+    services::impor_tabel::delete_pemetaan(&app, &nama)?;
+    Ok(format!("Pemetaan '{}' berhasil dihapus", nama))
+}
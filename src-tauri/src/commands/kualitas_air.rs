@@ -1,111 +1,158 @@
 use tauri::{State, command, AppHandle}; 
-use sqlx::SqlitePool;
+use sqlx::{query::Query, sqlite::SqliteArguments, Sqlite, SqlitePool};
+use chrono::NaiveDate;
+use crate::models::baku_mutu::BakuMutuClass;
+use crate::models::ekspor_csv::{KolomEkspor, OpsiEksporCsv};
+use crate::models::ekspor_xlsx::OpsiEksporXlsx;
+use crate::models::hasil_perhitungan::{HasilIp, HasilStoret};
+use crate::models::import_pdf::{HasilEkstraksi, HasilImportPdf};
 use crate::models::kualitas_air::KualitasAirRecord;
+use crate::models::nilai_tersensor::KebijakanSensor;
+use crate::models::query_kualitas_air::{HalamanKualitasAir, QueryKualitasAir};
+use crate::models::suhu_alamiah::SuhuAlamiah;
 use crate::services;
 use tauri_plugin_dialog::DialogExt;
 
-// --- COMMAND 1: SIMPAN DATA (DENGAN DEBUGGING LENGKAP) ---
-#[command]
-pub async fn submit_kualitas_air(
-    pool: State<'_, SqlitePool>, 
-    data: KualitasAirRecord
-) -> Result<String, String> {
-
-    // 1. Log Start (Mata-mata Backend)
-    println!("🦀 [RUST] 1. Request Diterima. Mulai proses SQL...");
-    println!("      -> Nama Pos: {:?}", data.nama_pos);
-    
-    // 2. Definisi SQL
-    let sql = "
-        INSERT INTO kualitas_air (
-            nama_pos, das, wilayah_sungai, provinsi, kabupaten, tahun,
-            elevasi_pos, pelaksana, kecamatan, laboratorium, sungai, desa, koordinat_geografis,
-            tanggal_sampling, waktu_sampling,
-            temperatur, konduktivitas, kekeruhan, oksigen, ph, tds, tss, warna,
-            klorida, amoniak, nitrat, nitrit, fosfat, deterjen,
-            arsen, besi, mangan, tembaga, merkuri,
-            sianida, fluorida, belerang,
-            cod, bod, minyak_dan_lemak, fenol,
-            total_coliform, debit,
-            nilai_ip, status_ip, nilai_storet, status_storet
-        ) VALUES (
-            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13,
-            $14, $15,
-            $16, $17, $18, $19, $20, $21, $22, $23,
-            $24, $25, $26, $27, $28, $29,
-            $30, $31, $32, $33, $34,
-            $35, $36, $37,
-            $38, $39, $40, $41,
-            $42, $43,
-            $44, $45, $46, $47
-        )
-    ";
-
-    // 3. Eksekusi Query dengan Penanganan Error Eksplisit (Match Block)
-    let result = sqlx::query(sql)
-        .bind(&data.nama_pos)
-        .bind(&data.das)
-        .bind(&data.wilayah_sungai)
-        .bind(&data.provinsi)
-        .bind(&data.kabupaten)
-        .bind(&data.tahun)
-        .bind(&data.elevasi_pos)
+// Helper: Bind seluruh kolom tabel kualitas_air sesuai urutan $1..$41
+// (dipakai bersama oleh INSERT di submit dan UPDATE di update)
+// Metadata pos tidak ikut di-bind: disimpan di tabel pos dan dirujuk lewat pos_id.
+fn bind_record<'q>(
+    query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    data: &'q KualitasAirRecord
+) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    query
+        .bind(data.pos_id)
+        .bind(data.tahun)
         .bind(&data.pelaksana)
-        .bind(&data.kecamatan)
         .bind(&data.laboratorium)
-        .bind(&data.sungai)
-        .bind(&data.desa)
-        .bind(&data.koordinat_geografis)
         // Waktu
-        .bind(&data.tanggal_sampling)
-        .bind(&data.waktu_sampling)
+        .bind(data.tanggal_sampling)
+        .bind(data.waktu_sampling)
         // Fisika
-        .bind(&data.temperatur)
-        .bind(&data.konduktivitas)
-        .bind(&data.kekeruhan)
-        .bind(&data.oksigen)
-        .bind(&data.ph)
-        .bind(&data.tds)
-        .bind(&data.tss)
-        .bind(&data.warna)
+        .bind(data.temperatur)
+        .bind(data.konduktivitas)
+        .bind(data.kekeruhan)
+        .bind(data.oksigen)
+        .bind(data.ph)
+        .bind(data.tds)
+        .bind(data.tss)
+        .bind(data.warna)
         // Ion
-        .bind(&data.klorida)
+        .bind(data.klorida)
         // Nutrient
-        .bind(&data.amoniak)
-        .bind(&data.nitrat)
-        .bind(&data.nitrit)
-        .bind(&data.fosfat)
-        .bind(&data.deterjen)
+        .bind(data.amoniak)
+        .bind(data.nitrat)
+        .bind(data.nitrit)
+        .bind(data.fosfat)
+        .bind(data.deterjen)
         // Logam
-        .bind(&data.arsen)
-        .bind(&data.besi)
-        .bind(&data.mangan)
-        .bind(&data.tembaga)
-        .bind(&data.merkuri)
+        .bind(data.arsen)
+        .bind(data.besi)
+        .bind(data.mangan)
+        .bind(data.tembaga)
+        .bind(data.merkuri)
         // Anorganik
-        .bind(&data.sianida)
-        .bind(&data.fluorida)
-        .bind(&data.belerang)
+        .bind(data.sianida)
+        .bind(data.fluorida)
+        .bind(data.belerang)
         // Organik
-        .bind(&data.cod)
-        .bind(&data.bod)
-        .bind(&data.minyak_dan_lemak)
-        .bind(&data.fenol)
+        .bind(data.cod)
+        .bind(data.bod)
+        .bind(data.minyak_dan_lemak)
+        .bind(data.fenol)
         // Mikro
-        .bind(&data.total_coliform)
+        .bind(data.total_coliform)
         // Lain
-        .bind(&data.debit)
+        .bind(data.debit)
         // Hasil Hitung
-        .bind(&data.nilai_ip)
+        .bind(data.nilai_ip)
         .bind(&data.status_ip)
-        .bind(&data.nilai_storet)
+        .bind(data.kelas_ip)
+        .bind(data.baku_mutu_id)
+        .bind(data.kebijakan_sensor)
+        .bind(data.nilai_storet)
         .bind(&data.status_storet)
-        .execute(pool.inner())
+}
+
+// Helper: Suhu alamiah pos sampel (record baru tanpa pos_id dicocokkan dari nama & koordinat pos)
+async fn load_suhu_sampel(pool: &SqlitePool, data: &KualitasAirRecord) -> Result<Vec<SuhuAlamiah>, String> {
+    let mut conn = pool.acquire().await
+        .map_err(|e| format!("Gagal koneksi ke database: {}", e))?;
+    let pos_id = services::pos_service::cari_pos(&mut conn, data).await?;
+    services::suhu_alamiah::load_pos(pool, pos_id).await
+}
+
+// Helper: Hitung ulang IP dengan kelas / set baku mutu / kebijakan sensor yang tercatat di record
+// (kelas IP -> kelas default pos -> Kelas II), hasilnya ditulis ke record
+async fn hitung_ip(pool: &SqlitePool, data: &mut KualitasAirRecord) -> Result<(), String> {
+    let kelas = data.kelas_ip.or(data.kelas_baku_mutu).unwrap_or_default();
+    let kebijakan = data.kebijakan_sensor.unwrap_or_default();
+    let baku_mutu = services::baku_mutu::resolve(pool, kelas, data.baku_mutu_id).await?;
+    let tabel_suhu = load_suhu_sampel(pool, data).await?;
+    let hasil = services::ip_calc::calculate_ip(data, &baku_mutu, &tabel_suhu, kebijakan);
+
+    data.nilai_ip = Some(hasil.nilai_ip);
+    data.status_ip = Some(hasil.status);
+    data.kelas_ip = Some(kelas);
+    data.kebijakan_sensor = Some(kebijakan);
+    Ok(())
+}
+
+// Kolom INSERT kualitas_air (urutan sama dengan bind_record)
+const SQL_INSERT: &str = "
+    INSERT INTO kualitas_air (
+        pos_id, tahun, pelaksana, laboratorium,
+        tanggal_sampling, waktu_sampling,
+        temperatur, konduktivitas, kekeruhan, oksigen, ph, tds, tss, warna,
+        klorida, amoniak, nitrat, nitrit, fosfat, deterjen,
+        arsen, besi, mangan, tembaga, merkuri,
+        sianida, fluorida, belerang,
+        cod, bod, minyak_dan_lemak, fenol,
+        total_coliform, debit,
+        nilai_ip, status_ip, kelas_ip, baku_mutu_id, kebijakan_sensor, nilai_storet, status_storet
+    ) VALUES (
+        $1, $2, $3, $4,
+        $5, $6,
+        $7, $8, $9, $10, $11, $12, $13, $14,
+        $15, $16, $17, $18, $19, $20,
+        $21, $22, $23, $24, $25,
+        $26, $27, $28,
+        $29, $30, $31, $32,
+        $33, $34,
+        $35, $36, $37, $38, $39, $40, $41
+    )
+";
+
+// --- COMMAND 1: SIMPAN DATA (DENGAN DEBUGGING LENGKAP) ---
+#[command]
+pub async fn submit_kualitas_air(
+    pool: State<'_, SqlitePool>, 
+    mut data: KualitasAirRecord
+) -> Result<String, String> {
+
+    // 1. Log Start (Mata-mata Backend)
+    println!("🦀 [RUST] 1. Request Diterima. Mulai proses SQL...");
+    println!("      -> Nama Pos: {:?}", data.nama_pos);
+
+    // 2. Hitung IP di backend (nilai IP dari frontend tidak dipercaya)
+    hitung_ip(pool.inner(), &mut data).await?;
+    
+    // 3. Daftarkan / cari pos dulu, lalu simpan sampel dalam transaksi yang sama
+    let mut tx = pool.begin().await
+        .map_err(|e| format!("Gagal memulai transaksi: {}", e))?;
+
+    data.pos_id = services::pos_service::resolve_pos(&mut tx, &data).await?;
+
+    let result = bind_record(sqlx::query(SQL_INSERT), &data)
+        .execute(&mut *tx)
         .await;
 
     // 4. Cek Hasil dan Lapor ke Terminal
     match result {
-        Ok(_) => {
+        Ok(res) => {
+            services::nilai_tersensor::simpan(&mut tx, res.last_insert_rowid(), &data.tersensor).await?;
+            tx.commit().await
+                .map_err(|e| format!("Gagal menyimpan data: {}", e))?;
             println!("✅ [RUST] 2. Query Berhasil! Data tersimpan.");
             Ok("Data berhasil disimpan ke database Local!".to_string())
         }
@@ -119,16 +166,32 @@ pub async fn submit_kualitas_air(
 
 
 // --- COMMAND 2: HITUNG IP (TETAP ADA) ---
+// Kelas dipilih dari argumen, lalu kelas default pos, lalu Kelas II.
+// Jika baku_mutu_id diisi, set baku mutu kustom dari database yang dipakai.
+// Mengembalikan rincian per parameter agar form bisa menandai parameter yang melampaui.
+// Nilai tersensor diganti sesuai kebijakan_sensor (default: batas deteksi / LOD).
 #[command]
-pub async fn calculate_ip_preview(data: KualitasAirRecord) -> Result<(f64, String), String> {
-    let result = services::ip_calc::calculate_ip(&data);
-    Ok(result)
+pub async fn calculate_ip_preview(
+    pool: State<'_, SqlitePool>,
+    data: KualitasAirRecord,
+    kelas: Option<BakuMutuClass>,
+    baku_mutu_id: Option<i64>,
+    kebijakan_sensor: Option<KebijakanSensor>
+) -> Result<(HasilIp, BakuMutuClass), String> {
+    let kelas = kelas.or(data.kelas_baku_mutu).unwrap_or_default();
+    let kebijakan = kebijakan_sensor.or(data.kebijakan_sensor).unwrap_or_default();
+    let baku_mutu = services::baku_mutu::resolve(pool.inner(), kelas, baku_mutu_id.or(data.baku_mutu_id)).await?;
+    let tabel_suhu = load_suhu_sampel(pool.inner(), &data).await?;
+    let hasil = services::ip_calc::calculate_ip(&data, &baku_mutu, &tabel_suhu, kebijakan);
+    Ok((hasil, kelas))
 }
 
 
 // --- COMMAND 3: IMPORT PDF (TETAP ADA) ---
+// Mengembalikan record + laporan asal tiap nilai (baris, kata kunci, keyakinan)
+// Metadata yang tidak tercetak di laporan dilengkapi dari data pos tersimpan.
 #[command]
-pub async fn import_pdf(app: AppHandle) -> Result<KualitasAirRecord, String> {
+pub async fn import_pdf(app: AppHandle, pool: State<'_, SqlitePool>) -> Result<Vec<HasilEkstraksi>, String> {
     // 1. Buka Dialog Native
     let file_path = app.dialog()
         .file()
@@ -140,7 +203,11 @@ pub async fn import_pdf(app: AppHandle) -> Result<KualitasAirRecord, String> {
         Some(path) => {
             let path_str = path.to_string();
             // Panggil service pdf_engine
-            let result = services::pdf_engine::parse_pdf(path_str)?;
+            let templates = services::template_lab::load_all(&app)?;
+            let mut result = services::pdf_engine::parse_pdf(path_str, &templates)?;
+            for sampel in result.iter_mut() {
+                services::pos_service::lengkapi_dari_registry(pool.inner(), &mut sampel.record, &mut sampel.laporan).await?;
+            }
             Ok(result)
         },
         None => {
@@ -153,13 +220,14 @@ pub async fn import_pdf(app: AppHandle) -> Result<KualitasAirRecord, String> {
 // --- COMMAND 4: AMBIL SEMUA DATA (BARU) ---
 #[command]
 pub async fn get_all_kualitas_air(pool: State<'_, SqlitePool>) -> Result<Vec<KualitasAirRecord>, String> {
-    let sql = "SELECT * FROM kualitas_air ORDER BY id DESC";
+    let sql = "SELECT * FROM v_kualitas_air ORDER BY tanggal_sampling DESC, waktu_sampling DESC, id DESC";
     
-    let rows = sqlx::query_as::<_, KualitasAirRecord>(sql)
+    let mut rows = sqlx::query_as::<_, KualitasAirRecord>(sql)
         .fetch_all(pool.inner())
         .await
         .map_err(|e| format!("Gagal mengambil data: {}", e))?;
 
+    services::nilai_tersensor::lengkapi(pool.inner(), &mut rows).await?;
     Ok(rows)
 }
 
@@ -190,13 +258,17 @@ pub async fn delete_kualitas_air(
 }
 
 // --- COMMAND 6: EXPORT CSV ---
+// Data mengikuti filter & urutan daftar data; kolom, pemisah kolom & desimal dipilih pengguna.
 #[command]
 pub async fn export_kualitas_air_csv
 (
     app: AppHandle,
-    pool: State<'_, SqlitePool>
+    pool: State<'_, SqlitePool>,
+    opsi: Option<OpsiEksporCsv>
 ) -> Result<String, String>
 {
+    let opsi = opsi.unwrap_or_default();
+
     let file_path = app.dialog()
         .file()
         .add_filter("CSV Files", &["csv"])
@@ -209,16 +281,14 @@ pub async fn export_kualitas_air_csv
         {
             let path_str = path.to_string();
 
-            // ambil semua data dari DB
-            let sql = "SELECT * FROM kualitas_air ORDER BY id DESC";
-            let data = sqlx::query_as::<_, KualitasAirRecord>(sql)
-                .fetch_all(pool.inner())
-                .await
-                .map_err(|e| format!("Gagal mengambil data dari database: {}", e))?;
+            // ambil data yang lolos filter dari DB
+            let data = services::query_kualitas_air::semua(
+                pool.inner(), &opsi.filter, opsi.urut.as_deref(), opsi.arah
+            ).await?;
 
-            services::csv_service::export_to_csv(&data, &path_str)?;
+            services::csv_service::export_to_csv(&data, &opsi, &path_str)?;
 
-            Ok(format!("Data berhasil diexport ke: {}", path_str))
+            Ok(format!("{} data berhasil diexport ke: {}", data.len(), path_str))
         },
         None =>
         {
@@ -226,4 +296,379 @@ pub async fn export_kualitas_air_csv
             Err("Export dibatalkan pengguna".to_string())
         }
     }
-}
\ No newline at end of file
+}
+
+// --- COMMAND 6B: DAFTAR KOLOM EXPORT ---
+#[command]
+pub fn get_kolom_ekspor() -> Vec<KolomEkspor> {
+    services::csv_service::daftar_kolom()
+}
+
+// --- COMMAND 6C: EXPORT EXCEL (.XLSX) ---
+// Sheet data (angka bertipe, judul bersatuan, tanda melampaui baku mutu) + sheet ringkasan IP/STORET per pos.
+#[command]
+pub async fn export_kualitas_air_xlsx(
+    app: AppHandle,
+    pool: State<'_, SqlitePool>,
+    opsi: Option<OpsiEksporXlsx>
+) -> Result<String, String> {
+    let opsi = opsi.unwrap_or_default();
+
+    let file_path = app.dialog()
+        .file()
+        .add_filter("Excel Files", &["xlsx"])
+        .set_file_name("data_kualitas_air.xlsx")
+        .blocking_save_file();
+
+    let Some(path) = file_path else {
+        return Err("Export dibatalkan pengguna".to_string());
+    };
+    let path_str = path.to_string();
+
+    let data = services::query_kualitas_air::semua(
+        pool.inner(), &opsi.filter, opsi.urut.as_deref(), opsi.arah
+    ).await?;
+    let acuan = services::xlsx_service::AcuanBakuMutu::muat(pool.inner(), &data).await?;
+
+    services::xlsx_service::export_to_xlsx(&data, &opsi, &acuan, &path_str)?;
+
+    println!("✅ [RUST EXPORT] {} data -> {}", data.len(), path_str);
+    Ok(format!("{} data berhasil diexport ke: {}", data.len(), path_str))
+}
+
+// --- COMMAND 7: HITUNG STORET PER POS & RENTANG TANGGAL ---
+// Hasil ditulis balik ke kolom nilai_storet/status_storet seluruh sampel yang dipakai.
+#[command]
+pub async fn calculate_storet(
+    pool: State<'_, SqlitePool>,
+    nama_pos: String,
+    tanggal_awal: Option<NaiveDate>,
+    tanggal_akhir: Option<NaiveDate>,
+    kelas: Option<BakuMutuClass>,
+    baku_mutu_id: Option<i64>,
+    kebijakan_sensor: Option<KebijakanSensor>
+) -> Result<HasilStoret, String> {
+    println!("🦀 [RUST STORET] Pos: {}, Rentang: {:?} s/d {:?}", nama_pos, tanggal_awal, tanggal_akhir);
+
+    // 1. Ambil sampel pos dalam rentang tanggal (tanggal ISO, bisa dibandingkan langsung di SQL).
+    // Jika rentang diisi, sampel tanpa tanggal tidak ikut karena tidak bisa dipastikan masuk rentang.
+    let sql = "
+        SELECT * FROM v_kualitas_air
+        WHERE nama_pos = $1
+          AND ($2 IS NULL OR tanggal_sampling >= $2)
+          AND ($3 IS NULL OR tanggal_sampling <= $3)
+        ORDER BY tanggal_sampling, waktu_sampling, id
+    ";
+    let mut dataset: Vec<KualitasAirRecord> = sqlx::query_as::<_, KualitasAirRecord>(sql)
+        .bind(&nama_pos)
+        .bind(tanggal_awal)
+        .bind(tanggal_akhir)
+        .fetch_all(pool.inner())
+        .await
+        .map_err(|e| format!("Gagal mengambil data: {}", e))?;
+
+    if dataset.is_empty() {
+        return Err(format!("Tidak ada data untuk pos {} pada rentang tersebut", nama_pos));
+    }
+    // STORET butuh data time series: skor 0 (Kelas A) dari satu sampel akan tersimpan sebagai "memenuhi"
+    if dataset.len() < 2 {
+        return Err(format!(
+            "STORET membutuhkan minimal 2 sampel, pos {} hanya memiliki {} sampel pada rentang tersebut",
+            nama_pos, dataset.len()
+        ));
+    }
+    services::nilai_tersensor::lengkapi(pool.inner(), &mut dataset).await?;
+
+    // 2. Tentukan baku mutu: argumen -> kelas default pos -> Kelas II
+    let kelas = kelas
+        .or_else(|| dataset.iter().find_map(|record| record.kelas_baku_mutu))
+        .unwrap_or_default();
+    let kebijakan = kebijakan_sensor.unwrap_or_default();
+    let baku_mutu = services::baku_mutu::resolve(pool.inner(), kelas, baku_mutu_id).await?;
+    // Satu nama pos bisa terdaftar sebagai beberapa pos (koordinat berbeda): muat suhu alamiah seluruhnya
+    let mut pos_ids: Vec<i64> = dataset.iter().filter_map(|record| record.pos_id).collect();
+    pos_ids.sort_unstable();
+    pos_ids.dedup();
+    let mut tabel_suhu = Vec::new();
+    for pos_id in pos_ids {
+        tabel_suhu.extend(services::suhu_alamiah::load_pos(pool.inner(), Some(pos_id)).await?);
+    }
+
+    // 3. Hitung
+    let (nilai_storet, status, rincian) = services::storet_calc::calculate_storet_collection(&dataset, &baku_mutu, &tabel_suhu, kebijakan);
+
+    // 4. Tulis balik ke database dalam satu transaksi
+    let mut tx = pool.begin().await
+        .map_err(|e| format!("Gagal memulai transaksi: {}", e))?;
+
+    for record in &dataset {
+        sqlx::query("UPDATE kualitas_air SET nilai_storet = ?, status_storet = ? WHERE id = ?")
+            .bind(nilai_storet)
+            .bind(&status)
+            .bind(record.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Gagal menyimpan hasil STORET: {}", e))?;
+    }
+
+    tx.commit().await
+        .map_err(|e| format!("Gagal menyimpan hasil STORET: {}", e))?;
+
+    println!("✅ [RUST STORET] {} sampel -> {} ({})", dataset.len(), nilai_storet, status);
+
+    Ok(HasilStoret {
+        nama_pos,
+        jumlah_sampel: dataset.len(),
+        nilai_storet,
+        status,
+        kelas,
+        baku_mutu_id,
+        kebijakan_sensor: kebijakan,
+        parameter: rincian,
+    })
+}
+
+
+// --- COMMAND 8: UBAH DATA BERDASARKAN ID ---
+// IP dihitung ulang dari nilai parameter yang baru sebelum disimpan.
+#[command]
+pub async fn update_kualitas_air(
+    pool: State<'_, SqlitePool>,
+    id: i64,
+    mut data: KualitasAirRecord
+) -> Result<String, String> {
+    println!("🦀 [RUST UPDATE] Mengubah record dengan ID: {}", id);
+
+    // 1. Hitung ulang IP dengan kelas / set baku mutu yang tercatat di record
+    hitung_ip(pool.inner(), &mut data).await?;
+
+    // 2. Definisi SQL (urutan kolom sama dengan INSERT di submit_kualitas_air)
+    let sql = "
+        UPDATE kualitas_air SET
+            pos_id = $1, tahun = $2, pelaksana = $3, laboratorium = $4,
+            tanggal_sampling = $5, waktu_sampling = $6,
+            temperatur = $7, konduktivitas = $8, kekeruhan = $9, oksigen = $10, ph = $11, tds = $12, tss = $13, warna = $14,
+            klorida = $15, amoniak = $16, nitrat = $17, nitrit = $18, fosfat = $19, deterjen = $20,
+            arsen = $21, besi = $22, mangan = $23, tembaga = $24, merkuri = $25,
+            sianida = $26, fluorida = $27, belerang = $28,
+            cod = $29, bod = $30, minyak_dan_lemak = $31, fenol = $32,
+            total_coliform = $33, debit = $34,
+            nilai_ip = $35, status_ip = $36, kelas_ip = $37, baku_mutu_id = $38, kebijakan_sensor = $39,
+            nilai_storet = $40, status_storet = $41
+        WHERE id = $42
+    ";
+
+    // 3. Eksekusi dalam transaksi (pos dicari / didaftarkan dari metadata header)
+    let mut tx = pool.begin().await
+        .map_err(|e| format!("Gagal memulai transaksi: {}", e))?;
+
+    data.pos_id = services::pos_service::resolve_pos(&mut tx, &data).await?;
+
+    let result = bind_record(sqlx::query(sql), &data)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Gagal mengubah data: {}", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err(format!("Data dengan ID {} tidak ditemukan", id));
+    }
+
+    services::nilai_tersensor::simpan(&mut tx, id, &data.tersensor).await?;
+
+    tx.commit().await
+        .map_err(|e| format!("Gagal mengubah data: {}", e))?;
+
+    println!("✅ [RUST UPDATE] Berhasil mengubah record ID {}", id);
+    Ok(format!("Data berhasil diubah (ID: {})", id))
+}
+
+
+// --- COMMAND 9: IMPORT BANYAK PDF SEKALIGUS ---
+// Sumber file: daftar path, satu folder, atau (jika keduanya kosong) dialog multi-file.
+// Hasil per file (record, peringatan, atau error) untuk direview sebelum disimpan.
+#[command]
+pub async fn import_pdf_batch(
+    app: AppHandle,
+    pool: State<'_, SqlitePool>,
+    paths: Option<Vec<String>>,
+    folder: Option<String>
+) -> Result<Vec<HasilImportPdf>, String> {
+    // 1. Kumpulkan daftar file
+    let mut files = paths.unwrap_or_default();
+    if let Some(folder) = folder {
+        files.extend(services::pdf_engine::list_pdf_folder(&folder)?);
+    }
+    if files.is_empty() {
+        let pilihan = app.dialog()
+            .file()
+            .add_filter("PDF Files", &["pdf"])
+            .blocking_pick_files()
+            .ok_or("Pemilihan file dibatalkan".to_string())?;
+        files = pilihan.iter().map(|path| path.to_string()).collect();
+    }
+
+    if files.is_empty() {
+        return Err("Tidak ada file PDF yang dipilih".to_string());
+    }
+
+    // 2. Ekstraksi paralel
+    println!("🦀 [RUST BATCH] Mengekstrak {} file PDF...", files.len());
+    let templates = services::template_lab::load_all(&app)?;
+    let mut hasil = services::pdf_engine::parse_pdf_batch(files, templates).await;
+
+    // 3. Lengkapi metadata dari data pos tersimpan, lalu cek ulang kelengkapan
+    for item in hasil.iter_mut() {
+        if let (Some(record), Some(laporan)) = (item.record.as_mut(), item.laporan.as_mut()) {
+            services::pos_service::lengkapi_dari_registry(pool.inner(), record, laporan).await?;
+            item.peringatan = services::pdf_engine::cek_kelengkapan(record);
+        }
+    }
+
+    let gagal = hasil.iter().filter(|h| h.error.is_some()).count();
+    println!("✅ [RUST BATCH] Selesai: {} berhasil, {} gagal", hasil.len() - gagal, gagal);
+
+    Ok(hasil)
+}
+
+// Helper: Hitung IP lalu simpan seluruh record dalam satu transaksi (dipakai batch PDF & impor tabel).
+// Jika satu gagal, tidak ada yang tersimpan; error memuat urutan record (mulai dari 0) yang gagal, jika ada.
+pub(crate) async fn simpan_batch(
+    pool: &SqlitePool,
+    mut data: Vec<KualitasAirRecord>
+) -> Result<Vec<i64>, (Option<usize>, String)> {
+    // 1. Hitung IP seluruh record sebelum transaksi dibuka
+    for (idx, record) in data.iter_mut().enumerate() {
+        hitung_ip(pool, record).await.map_err(|e| (Some(idx), e))?;
+    }
+
+    // 2. Simpan semuanya dalam satu transaksi
+    let mut tx = pool.begin().await
+        .map_err(|e| (None, format!("Gagal memulai transaksi: {}", e)))?;
+
+    let mut ids = Vec::with_capacity(data.len());
+    for (idx, mut record) in data.into_iter().enumerate() {
+        record.pos_id = services::pos_service::resolve_pos(&mut tx, &record).await
+            .map_err(|e| (Some(idx), e))?;
+
+        let id = bind_record(sqlx::query(SQL_INSERT), &record)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| (Some(idx), format!("Gagal menyimpan data: {}", e)))?
+            .last_insert_rowid();
+        services::nilai_tersensor::simpan(&mut tx, id, &record.tersensor).await
+            .map_err(|e| (Some(idx), e))?;
+        ids.push(id);
+    }
+
+    tx.commit().await
+        .map_err(|e| (None, format!("Gagal menyimpan data: {}", e)))?;
+
+    Ok(ids)
+}
+
+// --- COMMAND 10: SIMPAN BANYAK DATA SEKALIGUS (HASIL REVIEW BATCH) ---
+// Seluruh record disimpan dalam satu transaksi: jika satu gagal, tidak ada yang tersimpan.
+// IP dihitung di backend untuk setiap record. Mengembalikan ID record baru sesuai urutan input.
+#[command]
+pub async fn submit_kualitas_air_batch(
+    pool: State<'_, SqlitePool>,
+    data: Vec<KualitasAirRecord>
+) -> Result<Vec<i64>, String> {
+    let ids = simpan_batch(pool.inner(), data).await
+        .map_err(|(idx, e)| match idx {
+            Some(idx) => format!("Data ke-{}: {}", idx + 1, e),
+            None => e,
+        })?;
+
+    println!("✅ [RUST BATCH] {} record tersimpan", ids.len());
+    Ok(ids)
+}
+
+// --- COMMAND 11: DAFTAR DATA DENGAN FILTER, URUTAN & PAGINASI ---
+// Filter & urutan dijalankan di SQLite, frontend hanya menerima satu halaman data
+// beserta jumlah total dan ringkasan status IP untuk seluruh hasil filter.
+#[command]
+pub async fn query_kualitas_air(
+    pool: State<'_, SqlitePool>,
+    query: QueryKualitasAir
+) -> Result<HalamanKualitasAir, String> {
+    services::query_kualitas_air::cari(pool.inner(), &query).await
+}
+
+// --- COMMAND 12: LAPORAN HASIL ANALISA (PDF) SATU SAMPEL ---
+// Baku mutu mengikuti perhitungan IP sampel (kelas IP / set kustom -> kelas pos -> Kelas II).
+// Laporan mencetak IP tersimpan (sama dengan daftar data); IP dihitung ulang hanya untuk tanda
+// "melampaui" di tabel, dan laporan ditolak jika hasilnya tidak sama dengan nilai tersimpan.
+#[command]
+pub async fn export_laporan_pdf(
+    app: AppHandle,
+    pool: State<'_, SqlitePool>,
+    id: i64
+) -> Result<String, String> {
+    println!("🦀 [RUST LAPORAN] Menyusun laporan PDF untuk ID: {}", id);
+
+    let mut data = sqlx::query_as::<_, KualitasAirRecord>("SELECT * FROM v_kualitas_air WHERE id = ?")
+        .bind(id)
+        .fetch_all(pool.inner())
+        .await
+        .map_err(|e| format!("Gagal mengambil data: {}", e))?;
+    services::nilai_tersensor::lengkapi(pool.inner(), &mut data).await?;
+    let record = data.pop().ok_or_else(|| format!("Data dengan ID {} tidak ditemukan", id))?;
+
+    let kelas = record.kelas_ip.or(record.kelas_baku_mutu).unwrap_or_default();
+    let (baku_mutu, nama_baku_mutu) = match record.baku_mutu_id {
+        Some(set_id) => {
+            let set = services::baku_mutu::load_set(pool.inner(), set_id).await?;
+            (set.parameter, set.nama)
+        }
+        None => (services::baku_mutu::standar_kelas(kelas), format!("{} (PP No. 22 Tahun 2021)", kelas.label())),
+    };
+    let tabel_suhu = services::suhu_alamiah::load_pos(pool.inner(), record.pos_id).await?;
+    let mut hasil_ip = services::ip_calc::calculate_ip(
+        &record, &baku_mutu, &tabel_suhu, record.kebijakan_sensor.unwrap_or_default()
+    );
+
+    // Nilai resmi = nilai tersimpan; beda berarti baku mutu / suhu alamiah berubah sejak data disimpan
+    let (Some(nilai_ip), Some(status_ip)) = (record.nilai_ip, record.status_ip.clone()) else {
+        return Err(format!("IP data dengan ID {} belum dihitung, simpan ulang data sebelum mencetak laporan", id));
+    };
+    if (nilai_ip - hasil_ip.nilai_ip).abs() > 0.005 || status_ip != hasil_ip.status {
+        return Err(format!(
+            "IP tersimpan ({:.2}, {}) berbeda dengan hasil hitung ulang ({:.2}, {}), simpan ulang data sebelum mencetak laporan",
+            nilai_ip, status_ip, hasil_ip.nilai_ip, hasil_ip.status
+        ));
+    }
+    hasil_ip.nilai_ip = nilai_ip;
+    hasil_ip.status = status_ip;
+
+    // Nama file bawaan: Laporan_<pos>_<tanggal>.pdf (karakter selain huruf/angka jadi "_")
+    let nama_file: String = format!(
+        "Laporan_{}_{}",
+        record.nama_pos.as_deref().unwrap_or("Kualitas_Air"),
+        record.tanggal_sampling.map(|t| t.to_string()).unwrap_or_default()
+    ).chars().map(|c| if c.is_alphanumeric() || c == '-' { c } else { '_' }).collect();
+
+    let file_path = app.dialog()
+        .file()
+        .add_filter("PDF Files", &["pdf"])
+        .set_file_name(format!("{}.pdf", nama_file.trim_end_matches('_')))
+        .blocking_save_file();
+
+    let Some(path) = file_path else {
+        return Err("Export dibatalkan pengguna".to_string());
+    };
+    let path_str = path.to_string();
+
+    let laporan = services::laporan_pdf::DataLaporan {
+        record: &record,
+        baku_mutu: &baku_mutu,
+        nama_baku_mutu,
+        hasil_ip: &hasil_ip,
+        tanggal_cetak: chrono::Local::now().date_naive(),
+    };
+    services::laporan_pdf::simpan_laporan(&laporan, &path_str)?;
+
+    println!("✅ [RUST LAPORAN] Laporan tersimpan: {}", path_str);
+    Ok(format!("Laporan berhasil disimpan ke: {}", path_str))
+}
@@ -1 +1,6 @@
-pub mod kualitas_air;
\ No newline at end of file
+pub mod kualitas_air;
+pub mod baku_mutu;
+pub mod suhu_alamiah;
+pub mod pos;
+pub mod template_lab;
+pub mod impor_tabel;
@@ -0,0 +1,133 @@
+use tauri::{State, command};
+use sqlx::SqlitePool;
+use crate::models::pos::Pos;
+
+// Helper: Validasi data pos sebelum disimpan
+fn validate(data: &Pos) -> Result<(), String> {
+    if data.nama_pos.trim().is_empty() {
+        return Err("Nama pos wajib diisi".to_string());
+    }
+    Ok(())
+}
+
+// Koordinat kosong disimpan sebagai NULL agar deduplikasi (nama, koordinat) konsisten
+fn koordinat(data: &Pos) -> Option<&str> {
+    data.koordinat_geografis.as_deref().map(str::trim).filter(|k| !k.is_empty())
+}
+
+// --- COMMAND 1: DAFTAR SEMUA POS ---
+#[command]
+pub async fn get_all_pos(pool: State<'_, SqlitePool>) -> Result<Vec<Pos>, String> {
+    sqlx::query_as::<_, Pos>("SELECT * FROM pos ORDER BY nama_pos")
+        .fetch_all(pool.inner())
+        .await
+        .map_err(|e| format!("Gagal mengambil data pos: {}", e))
+}
+
+// --- COMMAND 2: BUAT POS BARU ---
+#[command]
+pub async fn create_pos(
+    pool: State<'_, SqlitePool>,
+    data: Pos
+) -> Result<i64, String> {
+    validate(&data)?;
+
+    let sql = "
+        INSERT INTO pos (
+            nama_pos, das, wilayah_sungai, provinsi, kabupaten, kecamatan, desa, sungai,
+            elevasi_pos, koordinat_geografis, kelas_baku_mutu
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+    ";
+
+    let id = sqlx::query(sql)
+        .bind(data.nama_pos.trim())
+        .bind(&data.das)
+        .bind(&data.wilayah_sungai)
+        .bind(&data.provinsi)
+        .bind(&data.kabupaten)
+        .bind(&data.kecamatan)
+        .bind(&data.desa)
+        .bind(&data.sungai)
+        .bind(&data.elevasi_pos)
+        .bind(koordinat(&data))
+        .bind(data.kelas_baku_mutu)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| format!("Gagal menyimpan pos: {}", e))?
+        .last_insert_rowid();
+
+    println!("✅ [RUST] Pos '{}' tersimpan (ID: {})", data.nama_pos, id);
+    Ok(id)
+}
+
+// --- COMMAND 3: EDIT POS ---
+// Perubahan langsung berlaku untuk seluruh sampel yang merujuk pos ini.
+#[command]
+pub async fn update_pos(
+    pool: State<'_, SqlitePool>,
+    id: i64,
+    data: Pos
+) -> Result<String, String> {
+    validate(&data)?;
+
+    let sql = "
+        UPDATE pos SET
+            nama_pos = $1, das = $2, wilayah_sungai = $3, provinsi = $4, kabupaten = $5,
+            kecamatan = $6, desa = $7, sungai = $8, elevasi_pos = $9, koordinat_geografis = $10,
+            kelas_baku_mutu = $11
+        WHERE id = $12
+    ";
+
+    let result = sqlx::query(sql)
+        .bind(data.nama_pos.trim())
+        .bind(&data.das)
+        .bind(&data.wilayah_sungai)
+        .bind(&data.provinsi)
+        .bind(&data.kabupaten)
+        .bind(&data.kecamatan)
+        .bind(&data.desa)
+        .bind(&data.sungai)
+        .bind(&data.elevasi_pos)
+        .bind(koordinat(&data))
+        .bind(data.kelas_baku_mutu)
+        .bind(id)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| format!("Gagal mengubah pos: {}", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err(format!("Pos dengan ID {} tidak ditemukan", id));
+    }
+
+    Ok(format!("Pos berhasil diubah (ID: {})", id))
+}
+
+// --- COMMAND 4: HAPUS POS ---
+// Pos yang masih dirujuk sampel tidak boleh dihapus.
+#[command]
+pub async fn delete_pos(
+    pool: State<'_, SqlitePool>,
+    id: i64
+) -> Result<String, String> {
+    let jumlah_sampel: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM kualitas_air WHERE pos_id = ?")
+        .bind(id)
+        .fetch_one(pool.inner())
+        .await
+        .map_err(|e| format!("Gagal menghapus pos: {}", e))?;
+
+    if jumlah_sampel > 0 {
+        return Err(format!("Pos masih dipakai oleh {} data sampel dan tidak bisa dihapus", jumlah_sampel));
+    }
+
+    let result = sqlx::query("DELETE FROM pos WHERE id = ?")
+        .bind(id)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| format!("Gagal menghapus pos: {}", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err(format!("Pos dengan ID {} tidak ditemukan", id));
+    }
+
+    Ok(format!("Pos berhasil dihapus (ID: {})", id))
+}
@@ -0,0 +1,66 @@
+use tauri::{State, command};
+use sqlx::SqlitePool;
+use crate::models::suhu_alamiah::SuhuAlamiah;
+use crate::services;
+
+// --- COMMAND 1: AMBIL SUHU ALAMIAH SATU POS ---
+#[command]
+pub async fn get_suhu_alamiah(
+    pool: State<'_, SqlitePool>,
+    pos_id: i64
+) -> Result<Vec<SuhuAlamiah>, String> {
+    services::suhu_alamiah::load_pos(pool.inner(), Some(pos_id)).await
+}
+
+// --- COMMAND 2: SIMPAN / PERBARUI SUHU ALAMIAH (PER POS & BULAN) ---
+#[command]
+pub async fn save_suhu_alamiah(
+    pool: State<'_, SqlitePool>,
+    data: SuhuAlamiah
+) -> Result<String, String> {
+    let nama_pos: Option<String> = sqlx::query_scalar("SELECT nama_pos FROM pos WHERE id = ?")
+        .bind(data.pos_id)
+        .fetch_optional(pool.inner())
+        .await
+        .map_err(|e| format!("Gagal mencari pos: {}", e))?;
+    let Some(nama_pos) = nama_pos else {
+        return Err(format!("Pos dengan ID {} tidak ditemukan", data.pos_id));
+    };
+    if !(0..=12).contains(&data.bulan) {
+        return Err(format!("Bulan tidak valid: {} (0 = sepanjang tahun, 1-12)", data.bulan));
+    }
+
+    let sql = "
+        INSERT INTO suhu_alamiah (pos_id, bulan, suhu) VALUES ($1, $2, $3)
+        ON CONFLICT (pos_id, bulan) DO UPDATE SET suhu = excluded.suhu
+    ";
+
+    sqlx::query(sql)
+        .bind(data.pos_id)
+        .bind(data.bulan)
+        .bind(data.suhu)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| format!("Gagal menyimpan suhu alamiah: {}", e))?;
+
+    Ok(format!("Suhu alamiah {} tersimpan", nama_pos))
+}
+
+// --- COMMAND 3: HAPUS SUHU ALAMIAH ---
+#[command]
+pub async fn delete_suhu_alamiah(
+    pool: State<'_, SqlitePool>,
+    id: i64
+) -> Result<String, String> {
+    let result = sqlx::query("DELETE FROM suhu_alamiah WHERE id = ?")
+        .bind(id)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| format!("Gagal menghapus data: {}", e))?;
+
+    if result.rows_affected() == 0 {
+        Err(format!("Suhu alamiah dengan ID {} tidak ditemukan", id))
+    } else {
+        Ok(format!("Suhu alamiah berhasil dihapus (ID: {})", id))
+    }
+}
@@ -0,0 +1,30 @@
+use tauri::{AppHandle, command};
+use crate::models::template_lab::TemplateLab;
+use crate::services;
+
+// --- COMMAND 1: DAFTAR TEMPLATE LAB (BAWAAN + FOLDER TEMPLATE) ---
+#[command]
+pub async fn get_all_template_lab(app: AppHandle) -> Result<Vec<TemplateLab>, String> {
+    services::template_lab::load_all(&app)
+}
+
+// --- COMMAND 2: SIMPAN TEMPLATE LAB (BUAT BARU / TIMPA) ---
+#[command]
+pub async fn save_template_lab(
+    app: AppHandle,
+    data: TemplateLab
+) -> Result<String, String> {
+    let path = services::template_lab::save(&app, &data)?;
+    println!("✅ [RUST] Template lab '{}' tersimpan: {:?}", data.nama, path);
+    Ok(format!("Template berhasil disimpan ke: {}", path.display()))
+}
+
+// --- COMMAND 3: HAPUS TEMPLATE LAB ---
+#[command]
+pub async fn delete_template_lab(
+    app: AppHandle,
+    nama: String
+) -> Result<String, String> {
+    services::template_lab::delete(&app, &nama)?;
+    Ok(format!("Template '{}' berhasil dihapus", nama))
+}
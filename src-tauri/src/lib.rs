@@ -32,9 +32,41 @@ pub fn run() {
             commands::kualitas_air::submit_kualitas_air,
             commands::kualitas_air::calculate_ip_preview,
             commands::kualitas_air::import_pdf,
+            commands::kualitas_air::import_pdf_batch,
+            commands::kualitas_air::submit_kualitas_air_batch,
             commands::kualitas_air::get_all_kualitas_air,
             commands::kualitas_air::delete_kualitas_air,
-            commands::kualitas_air::export_kualitas_air_csv
+            commands::kualitas_air::update_kualitas_air,
+            commands::kualitas_air::export_kualitas_air_csv,
+            commands::kualitas_air::get_kolom_ekspor,
+            commands::kualitas_air::export_kualitas_air_xlsx,
+            commands::kualitas_air::calculate_storet,
+            commands::kualitas_air::query_kualitas_air,
+            commands::kualitas_air::export_laporan_pdf,
+            commands::baku_mutu::get_all_baku_mutu,
+            commands::baku_mutu::get_baku_mutu_kelas,
+            commands::baku_mutu::create_baku_mutu,
+            commands::baku_mutu::clone_baku_mutu,
+            commands::baku_mutu::update_baku_mutu,
+            commands::baku_mutu::delete_baku_mutu,
+            commands::suhu_alamiah::get_suhu_alamiah,
+            commands::suhu_alamiah::save_suhu_alamiah,
+            commands::suhu_alamiah::delete_suhu_alamiah,
+            commands::pos::get_all_pos,
+            commands::pos::create_pos,
+            commands::pos::update_pos,
+            commands::pos::delete_pos,
+            commands::template_lab::get_all_template_lab,
+            commands::template_lab::save_template_lab,
+            commands::template_lab::delete_template_lab,
+            commands::impor_tabel::pilih_file_impor,
+            commands::impor_tabel::baca_file_impor,
+            commands::impor_tabel::get_field_impor,
+            commands::impor_tabel::pratinjau_impor,
+            commands::impor_tabel::simpan_impor,
+            commands::impor_tabel::get_all_pemetaan_impor,
+            commands::impor_tabel::save_pemetaan_impor,
+            commands::impor_tabel::delete_pemetaan_impor
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
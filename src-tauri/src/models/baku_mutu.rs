@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::NaiveDateTime;
+
+/// Kelas Mutu Air Sungai (PP 22 Tahun 2021, Lampiran VI)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, sqlx::Type)]
+pub enum BakuMutuClass {
+    #[serde(rename = "Kelas I")]
+    #[sqlx(rename = "Kelas I")]
+    KelasI, // Air baku air minum
+
+    #[default]
+    #[serde(rename = "Kelas II")]
+    #[sqlx(rename = "Kelas II")]
+    KelasII, // Prasarana rekreasi air, budidaya ikan air tawar
+
+    #[serde(rename = "Kelas III")]
+    #[sqlx(rename = "Kelas III")]
+    KelasIII, // Budidaya ikan air tawar, peternakan
+
+    #[serde(rename = "Kelas IV")]
+    #[sqlx(rename = "Kelas IV")]
+    KelasIV, // Mengairi pertanaman
+}
+
+impl BakuMutuClass {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BakuMutuClass::KelasI => "Kelas I",
+            BakuMutuClass::KelasII => "Kelas II",
+            BakuMutuClass::KelasIII => "Kelas III",
+            BakuMutuClass::KelasIV => "Kelas IV",
+        }
+    }
+
+    /// Baca kelas dari teks bebas: "Kelas II", "II", atau "2"
+    pub fn dari_teks(teks: &str) -> Option<Self> {
+        let teks = teks.trim();
+        let kode = teks.get(..5)
+            .filter(|awal| awal.eq_ignore_ascii_case("kelas"))
+            .map_or(teks, |_| teks[5..].trim());
+        match kode.to_uppercase().as_str() {
+            "I" | "1" => Some(BakuMutuClass::KelasI),
+            "II" | "2" => Some(BakuMutuClass::KelasII),
+            "III" | "3" => Some(BakuMutuClass::KelasIII),
+            "IV" | "4" => Some(BakuMutuClass::KelasIV),
+            _ => None,
+        }
+    }
+}
+
+// Jenis batas yang berlaku untuk sebuah parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+pub enum LimitType {
+    Max,     // Data tidak boleh > batas_atas
+    Min,     // Data tidak boleh < batas_bawah (DO)
+    Range,   // Data harus di antara batas_bawah & batas_atas (pH)
+    Deviasi, // Selisih dari suhu alamiah tidak boleh > batas_atas (Temperatur)
+}
+
+/// Satu baris baku mutu: parameter (nama field di KualitasAirRecord) + batasnya
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct BatasParameter {
+    pub parameter: String,
+    pub jenis: LimitType,
+    pub batas_bawah: Option<f64>,
+    pub batas_atas: Option<f64>,
+    pub satuan: Option<String>,  // Contoh: "mg/L"
+    pub rujukan: Option<String>, // Contoh: "Pergub Sultra No. 12/2020 Lampiran II"
+}
+
+/// Kumpulan baku mutu bernama yang disimpan pengguna (tabel baku_mutu_set + baku_mutu)
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct BakuMutuSet {
+    pub id: Option<i64>,
+    pub nama: String,
+    pub rujukan: Option<String>,
+    pub keterangan: Option<String>,
+
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub parameter: Vec<BatasParameter>,
+
+    pub created_at: Option<NaiveDateTime>,
+}
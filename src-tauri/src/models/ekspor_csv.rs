@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use crate::models::query_kualitas_air::{ArahUrutan, FilterKualitasAir};
+
+/// Pemisah kolom CSV
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PemisahCsv {
+    #[default]
+    Koma,      // ","
+    TitikKoma, // ";" (Excel dengan locale Indonesia)
+    Tab,
+}
+
+impl PemisahCsv {
+    pub fn byte(&self) -> u8 {
+        match self {
+            PemisahCsv::Koma => b',',
+            PemisahCsv::TitikKoma => b';',
+            PemisahCsv::Tab => b'\t',
+        }
+    }
+}
+
+/// Pemisah desimal angka di CSV
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DesimalCsv {
+    #[default]
+    Titik, // 1.25
+    Koma,  // 1,25 (Excel dengan locale Indonesia)
+}
+
+/// Opsi export CSV: data yang diexport mengikuti filter & urutan daftar data
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OpsiEksporCsv {
+    #[serde(default)]
+    pub filter: FilterKualitasAir,
+    #[serde(default)]
+    pub urut: Option<String>,
+    #[serde(default)]
+    pub arah: ArahUrutan,
+    #[serde(default)]
+    pub kolom: Vec<String>, // Kunci kolom sesuai urutan di file; kosong = seluruh kolom
+    #[serde(default)]
+    pub pemisah: PemisahCsv,
+    #[serde(default)]
+    pub desimal: DesimalCsv,
+}
+
+/// Kolom yang bisa dipilih untuk export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KolomEkspor {
+    pub kunci: String, // Nama field, dipakai di OpsiEksporCsv.kolom
+    pub judul: String, // Header di file, termasuk satuan untuk parameter
+}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use crate::models::query_kualitas_air::{ArahUrutan, FilterKualitasAir};
+
+/// Opsi export Excel: data mengikuti filter & urutan daftar data, kolom sama dengan export CSV
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OpsiEksporXlsx {
+    #[serde(default)]
+    pub filter: FilterKualitasAir,
+    #[serde(default)]
+    pub urut: Option<String>,
+    #[serde(default)]
+    pub arah: ArahUrutan,
+    #[serde(default)]
+    pub kolom: Vec<String>, // Kunci kolom sheet data; kosong = seluruh kolom
+}
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use crate::models::baku_mutu::{BakuMutuClass, LimitType};
+use crate::models::nilai_tersensor::KebijakanSensor;
+
+/// Rincian satu parameter dalam perhitungan Indeks Pencemaran
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RincianParameterIp {
+    pub parameter: String,
+    pub jenis: LimitType,
+    pub konsentrasi: f64,          // Ci: nilai hasil uji (setelah substitusi nilai tersensor)
+    pub tersensor: bool,           // true jika Ci berasal dari nilai < / > batas deteksi
+    pub batas_bawah: Option<f64>,  // Lij (Min / Range)
+    pub batas_atas: Option<f64>,   // Lij (Max / Range / Deviasi)
+    pub rasio: f64,                // Ci/Lij mentah (L/C untuk DO, deviasi/L untuk temperatur)
+    pub rasio_transformasi: f64,   // Setelah 1 + 5 log10(Ci/Lij) jika > 1
+    pub melampaui: bool,           // true jika rasio > 1 (tidak memenuhi baku mutu)
+}
+
+/// Hasil lengkap perhitungan Indeks Pencemaran satu sampel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HasilIp {
+    pub nilai_ip: f64,
+    pub status: String,
+    pub rasio_maks: f64, // (Ci/Lij)M pada rumus Nemerow
+    pub rasio_rata: f64, // (Ci/Lij)R pada rumus Nemerow
+    pub suhu_alamiah: f64, // Acuan deviasi temperatur yang dipakai (°C)
+    pub kebijakan_sensor: KebijakanSensor, // Substitusi nilai tersensor yang dipakai
+    pub parameter: Vec<RincianParameterIp>,
+}
+
+// Kategori Parameter pada tabel skor STORET
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Kategori {
+    Fisika,
+    Kimia,
+    Biologi,
+}
+
+/// Rincian satu parameter dalam perhitungan STORET (untuk laporan tahunan & audit kelas)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RincianParameterStoret {
+    pub parameter: String,
+    pub kategori: Kategori,
+    pub jenis: LimitType,
+    pub jumlah_sampel: usize,
+    pub jumlah_tersensor: usize, // Berapa sampel yang nilainya < / > batas deteksi
+    pub min: f64,
+    pub max: f64,
+    pub rata: f64,
+    pub batas_bawah: Option<f64>,
+    pub batas_atas: Option<f64>,
+    pub melanggar_max: bool,
+    pub melanggar_min: bool,
+    pub melanggar_rata: bool,
+    pub skor: f64, // Total denda parameter ini (0 jika memenuhi)
+}
+
+/// Hasil perhitungan STORET satu pos untuk rentang tanggal tertentu
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HasilStoret {
+    pub nama_pos: String,
+    pub jumlah_sampel: usize,
+    pub nilai_storet: f64,
+    pub status: String,
+    pub kelas: BakuMutuClass,
+    pub baku_mutu_id: Option<i64>,
+    pub kebijakan_sensor: KebijakanSensor,
+    pub parameter: Vec<RincianParameterStoret>,
+}
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use crate::models::ekspor_csv::{DesimalCsv, PemisahCsv};
+use crate::models::kualitas_air::KualitasAirRecord;
+
+/// Pemetaan kolom file CSV / Excel ke field KualitasAirRecord.
+/// Disimpan sebagai file .json agar bisa dipakai ulang untuk spreadsheet berformat sama.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PemetaanImpor {
+    pub nama: String,
+    #[serde(default)]
+    pub lembar: Option<String>, // Nama sheet Excel; None = sheet pertama
+    #[serde(default = "baris_header_default")]
+    pub baris_header: usize,    // Nomor baris judul kolom (mulai dari 1), baris di atasnya diabaikan
+    #[serde(default)]
+    pub pemisah: Option<PemisahCsv>, // Pemisah kolom CSV; None = deteksi otomatis
+    #[serde(default)]
+    pub desimal: DesimalCsv,    // Pemisah desimal angka berbentuk teks
+    #[serde(default)]
+    pub kolom: BTreeMap<String, String>, // Field -> judul kolom di file
+}
+
+fn baris_header_default() -> usize { 1 }
+
+/// Seluruh pemetaan tersimpan beserta file pemetaan yang dilewati karena tidak bisa dibaca
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaftarPemetaan {
+    pub pemetaan: Vec<PemetaanImpor>,
+    pub peringatan: Vec<String>,
+}
+
+/// Isi awal file yang dipilih, untuk menyusun pemetaan kolom
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileImpor {
+    pub path: String,
+    pub lembar: Vec<String>,      // Daftar sheet (kosong untuk CSV)
+    pub judul: Vec<String>,       // Judul kolom di baris header
+    pub contoh: Vec<Vec<String>>, // Beberapa baris data pertama
+    pub jumlah_baris: usize,
+    pub saran: BTreeMap<String, String>, // Tebakan pemetaan dari judul kolom
+}
+
+/// Hasil validasi satu baris file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BarisImpor {
+    pub baris: usize, // Nomor baris di file (mulai dari 1)
+    pub record: Option<KualitasAirRecord>, // None jika ada error
+    pub error: Vec<String>,
+    pub peringatan: Vec<String>,
+    pub duplikat: bool, // Pos, tanggal & waktu sama dengan data tersimpan / baris sebelumnya
+}
+
+/// Hasil pratinjau / penyimpanan impor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HasilImporTabel {
+    pub baris: Vec<BarisImpor>,
+    pub peringatan: Vec<String>, // Catatan per kolom, misal konversi satuan
+    pub jumlah_valid: usize,
+    pub jumlah_gagal: usize,
+    pub ids: Vec<i64>, // ID record tersimpan (kosong saat pratinjau)
+}
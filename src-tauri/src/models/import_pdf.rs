@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use crate::models::kualitas_air::KualitasAirRecord;
+
+/// Asal nilai satu field hasil ekstraksi PDF (untuk verifikasi manual)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SumberField {
+    pub field: String,            // Nama field di KualitasAirRecord
+    pub nomor_baris: usize,       // Nomor baris teks PDF (mulai dari 1)
+    pub baris: String,            // Isi baris sumber
+    pub kata_kunci: String,       // Kata kunci yang cocok
+    pub satuan: Option<String>,   // Satuan tercantum di laporan (sebelum dikonversi ke satuan baku)
+    pub keyakinan: f64,           // 0.0 - 1.0, semakin kecil semakin perlu dicek
+    pub catatan: Option<String>,  // Alasan keyakinan diturunkan
+}
+
+/// Satu baris teks PDF
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BarisTeks {
+    pub nomor_baris: usize,
+    pub baris: String,
+}
+
+/// Laporan ekstraksi satu file PDF
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LaporanEkstraksi {
+    pub template: String,                     // Template lab yang dipakai (hasil deteksi otomatis)
+    pub field: Vec<SumberField>,
+    pub sni_tidak_terpetakan: Vec<BarisTeks>, // Baris ber-"SNI" yang tidak menghasilkan nilai parameter
+}
+
+/// Hasil ekstraksi satu PDF: record + laporan asal tiap nilai
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HasilEkstraksi {
+    pub record: KualitasAirRecord,
+    pub laporan: LaporanEkstraksi,
+}
+
+/// Hasil impor satu file PDF dalam batch (untuk direview sebelum disimpan)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HasilImportPdf {
+    pub file: String,
+    pub sampel_ke: Option<usize>,          // Urutan kolom sampel (1, 2, ...) untuk PDF multi-sampel
+    pub record: Option<KualitasAirRecord>, // None jika file gagal dibaca
+    pub laporan: Option<LaporanEkstraksi>,
+    pub peringatan: Vec<String>,           // Field penting yang tidak ditemukan
+    pub error: Option<String>,
+}
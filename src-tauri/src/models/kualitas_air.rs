@@ -1,10 +1,13 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
-use chrono::NaiveDateTime;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+use crate::models::baku_mutu::BakuMutuClass;
+use crate::models::nilai_tersensor::{KebijakanSensor, NilaiTersensor};
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, FromRow)]
 pub struct KualitasAirRecord {
     pub id: Option<i64>,
+    pub pos_id: Option<i64>, // Referensi ke registry pos (metadata pos dibaca dari view v_kualitas_air)
     
     // --- METADATA HEADER ---
     pub nama_pos: Option<String>,
@@ -20,10 +23,12 @@ pub struct KualitasAirRecord {
     pub sungai: Option<String>,
     pub desa: Option<String>,
     pub koordinat_geografis: Option<String>,
+    pub kelas_baku_mutu: Option<BakuMutuClass>, // Kelas peruntukan default pos (PP 22/2021)
 
     // --- WAKTU SAMPLING ---
-    pub tanggal_sampling: Option<String>,
-    pub waktu_sampling: Option<String>,
+    // Tersimpan ISO 8601 ("YYYY-MM-DD" dan "HH:MM:SS") agar bisa diurutkan & difilter di SQL
+    pub tanggal_sampling: Option<NaiveDate>,
+    pub waktu_sampling: Option<NaiveTime>,
     
     // --- PARAMETER KUALITAS AIR ---
     // 1. Variabel Umum
@@ -87,6 +92,18 @@ pub struct KualitasAirRecord {
     #[serde(rename = "statusIp")]
     pub status_ip: Option<String>, // Contoh: "Cemar Sedang"
 
+    #[sqlx(rename = "kelas_ip")]
+    #[serde(rename = "kelasIp")]
+    pub kelas_ip: Option<BakuMutuClass>, // Kelas baku mutu yang dipakai saat menghitung IP
+
+    #[sqlx(rename = "baku_mutu_id")]
+    #[serde(rename = "bakuMutuId")]
+    pub baku_mutu_id: Option<i64>, // Set baku mutu kustom yang dipakai (NULL = tabel kelas bawaan)
+
+    #[sqlx(rename = "kebijakan_sensor")]
+    #[serde(rename = "kebijakanSensor")]
+    pub kebijakan_sensor: Option<KebijakanSensor>, // Substitusi nilai tersensor yang dipakai saat menghitung IP
+
     #[sqlx(rename = "nilai_storet")]
     #[serde(rename = "nilaiStoret")]
     pub nilai_storet: Option<f64>, // Contoh: -18.0
@@ -95,6 +112,203 @@ pub struct KualitasAirRecord {
     #[serde(rename = "statusStoret")]
     pub status_storet: Option<String>, // Contoh: "Kelas C"
 
+    // --- NILAI TERSENSOR (< / > BATAS DETEKSI) ---
+    // Disimpan di tabel nilai_tersensor, diisi terpisah setelah query
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub tersensor: Vec<NilaiTersensor>,
+
     // --- SYSTEM METADATA ---
     pub created_at: Option<NaiveDateTime>,
-}
\ No newline at end of file
+}
+
+/// Daftar parameter yang bisa diberi baku mutu (nama field di KualitasAirRecord)
+pub const NAMA_PARAMETER: [&str; 28] = [
+    "temperatur", "konduktivitas", "kekeruhan", "oksigen", "ph", "tds", "tss", "warna",
+    "klorida",
+    "amoniak", "nitrat", "nitrit", "fosfat", "deterjen",
+    "arsen", "besi", "mangan", "tembaga", "merkuri",
+    "sianida", "fluorida", "belerang",
+    "cod", "bod", "minyak_dan_lemak", "fenol",
+    "total_coliform",
+    "debit",
+];
+
+/// Satuan baku tiap parameter. Nilai tersimpan selalu dalam satuan ini;
+/// satuan lain dari laporan lab dikonversi saat impor.
+pub const SATUAN_PARAMETER: [(&str, &str); 28] = [
+    ("temperatur", "°C"), ("konduktivitas", "µS/cm"), ("kekeruhan", "NTU"), ("oksigen", "mg/L"),
+    ("ph", "-"), ("tds", "mg/L"), ("tss", "mg/L"), ("warna", "Pt-Co"),
+    ("klorida", "mg/L"),
+    ("amoniak", "mg/L"), ("nitrat", "mg/L"), ("nitrit", "mg/L"), ("fosfat", "mg/L"), ("deterjen", "mg/L"),
+    ("arsen", "mg/L"), ("besi", "mg/L"), ("mangan", "mg/L"), ("tembaga", "mg/L"), ("merkuri", "mg/L"),
+    ("sianida", "mg/L"), ("fluorida", "mg/L"), ("belerang", "mg/L"),
+    ("cod", "mg/L"), ("bod", "mg/L"), ("minyak_dan_lemak", "mg/L"), ("fenol", "mg/L"),
+    ("total_coliform", "MPN/100 mL"),
+    ("debit", "m³/detik"),
+];
+
+/// Nama parameter untuk laporan & header export (mengikuti lembar cetak laporan)
+pub const LABEL_PARAMETER: [(&str, &str); 28] = [
+    ("temperatur", "Temperatur"), ("konduktivitas", "Konduktivitas"), ("kekeruhan", "Kekeruhan"),
+    ("oksigen", "DO (Oksigen Terlarut)"), ("ph", "pH"), ("tds", "TDS (Zat Padat Terlarut)"),
+    ("tss", "TSS (Zat Padat Tersuspensi)"), ("warna", "Warna"),
+    ("klorida", "Klorida (Cl)"),
+    ("amoniak", "Amoniak (NH3-N)"), ("nitrat", "Nitrat (NO3-N)"), ("nitrit", "Nitrit (NO2-N)"),
+    ("fosfat", "Fosfat (PO4)"), ("deterjen", "Deterjen (MBAS)"),
+    ("arsen", "Arsen (As)"), ("besi", "Besi (Fe)"), ("mangan", "Mangan (Mn)"), ("tembaga", "Tembaga (Cu)"),
+    ("merkuri", "Merkuri (Hg)"),
+    ("sianida", "Sianida (CN)"), ("fluorida", "Fluorida (F)"), ("belerang", "Belerang (H2S)"),
+    ("cod", "COD"), ("bod", "BOD"), ("minyak_dan_lemak", "Minyak & Lemak"), ("fenol", "Fenol"),
+    ("total_coliform", "Total Coliform"),
+    ("debit", "Debit"),
+];
+
+/// Nama parameter untuk ditampilkan (None jika bukan parameter)
+pub fn label_parameter(parameter: &str) -> Option<&'static str> {
+    LABEL_PARAMETER.iter().find(|(p, _)| *p == parameter).map(|(_, label)| *label)
+}
+
+/// Satuan baku satu parameter (None jika bukan parameter)
+pub fn satuan_parameter(parameter: &str) -> Option<&'static str> {
+    SATUAN_PARAMETER.iter().find(|(p, _)| *p == parameter).map(|(_, satuan)| *satuan)
+}
+
+/// Field metadata teks yang bisa diisi dari header laporan (nama field di KualitasAirRecord)
+pub const NAMA_METADATA: [&str; 14] = [
+    "nama_pos", "das", "wilayah_sungai", "provinsi", "kabupaten", "kecamatan", "desa", "sungai",
+    "elevasi_pos", "koordinat_geografis", "pelaksana", "laboratorium",
+    "tanggal_sampling", "waktu_sampling",
+];
+
+impl KualitasAirRecord {
+    /// Waktu sampling lengkap (tanggal + jam). Jam 00:00 jika waktu sampling kosong.
+    pub fn waktu_sampling_lengkap(&self) -> Option<NaiveDateTime> {
+        let tanggal = self.tanggal_sampling?;
+        Some(tanggal.and_time(self.waktu_sampling.unwrap_or_default()))
+    }
+
+    /// Bulan sampling (1-12)
+    pub fn bulan_sampling(&self) -> Option<u32> {
+        self.tanggal_sampling.map(|d| d.month())
+    }
+
+    /// Ambil nilai parameter berdasarkan nama field (dipakai oleh tabel baku mutu)
+    pub fn nilai_parameter(&self, parameter: &str) -> Option<f64> {
+        match parameter {
+            "temperatur" => self.temperatur,
+            "konduktivitas" => self.konduktivitas,
+            "kekeruhan" => self.kekeruhan,
+            "oksigen" => self.oksigen,
+            "ph" => self.ph,
+            "tds" => self.tds,
+            "tss" => self.tss,
+            "warna" => self.warna,
+            "klorida" => self.klorida,
+            "amoniak" => self.amoniak,
+            "nitrat" => self.nitrat,
+            "nitrit" => self.nitrit,
+            "fosfat" => self.fosfat,
+            "deterjen" => self.deterjen,
+            "arsen" => self.arsen,
+            "besi" => self.besi,
+            "mangan" => self.mangan,
+            "tembaga" => self.tembaga,
+            "merkuri" => self.merkuri,
+            "sianida" => self.sianida,
+            "fluorida" => self.fluorida,
+            "belerang" => self.belerang,
+            "cod" => self.cod,
+            "bod" => self.bod,
+            "minyak_dan_lemak" => self.minyak_dan_lemak,
+            "fenol" => self.fenol,
+            "total_coliform" => self.total_coliform,
+            "debit" => self.debit,
+            _ => None,
+        }
+    }
+
+    /// Nilai parameter untuk perhitungan: nilai tersensor diganti sesuai kebijakan
+    /// (None jika parameter kosong atau diabaikan oleh kebijakan)
+    pub fn nilai_hitung(&self, parameter: &str, kebijakan: KebijakanSensor) -> Option<f64> {
+        let nilai = self.nilai_parameter(parameter)?;
+        match self.tersensor.iter().find(|t| t.parameter == parameter) {
+            Some(sensor) => sensor.substitusi(kebijakan),
+            None => Some(nilai),
+        }
+    }
+
+    /// Akses tulis nilai parameter berdasarkan nama field (dipakai oleh parser PDF)
+    pub fn parameter_mut(&mut self, parameter: &str) -> Option<&mut Option<f64>> {
+        match parameter {
+            "temperatur" => Some(&mut self.temperatur),
+            "konduktivitas" => Some(&mut self.konduktivitas),
+            "kekeruhan" => Some(&mut self.kekeruhan),
+            "oksigen" => Some(&mut self.oksigen),
+            "ph" => Some(&mut self.ph),
+            "tds" => Some(&mut self.tds),
+            "tss" => Some(&mut self.tss),
+            "warna" => Some(&mut self.warna),
+            "klorida" => Some(&mut self.klorida),
+            "amoniak" => Some(&mut self.amoniak),
+            "nitrat" => Some(&mut self.nitrat),
+            "nitrit" => Some(&mut self.nitrit),
+            "fosfat" => Some(&mut self.fosfat),
+            "deterjen" => Some(&mut self.deterjen),
+            "arsen" => Some(&mut self.arsen),
+            "besi" => Some(&mut self.besi),
+            "mangan" => Some(&mut self.mangan),
+            "tembaga" => Some(&mut self.tembaga),
+            "merkuri" => Some(&mut self.merkuri),
+            "sianida" => Some(&mut self.sianida),
+            "fluorida" => Some(&mut self.fluorida),
+            "belerang" => Some(&mut self.belerang),
+            "cod" => Some(&mut self.cod),
+            "bod" => Some(&mut self.bod),
+            "minyak_dan_lemak" => Some(&mut self.minyak_dan_lemak),
+            "fenol" => Some(&mut self.fenol),
+            "total_coliform" => Some(&mut self.total_coliform),
+            "debit" => Some(&mut self.debit),
+            _ => None,
+        }
+    }
+
+    /// Baca field metadata teks berdasarkan nama field (pasangan metadata_mut)
+    pub fn metadata(&self, field: &str) -> Option<&str> {
+        match field {
+            "nama_pos" => self.nama_pos.as_deref(),
+            "das" => self.das.as_deref(),
+            "wilayah_sungai" => self.wilayah_sungai.as_deref(),
+            "provinsi" => self.provinsi.as_deref(),
+            "kabupaten" => self.kabupaten.as_deref(),
+            "kecamatan" => self.kecamatan.as_deref(),
+            "desa" => self.desa.as_deref(),
+            "sungai" => self.sungai.as_deref(),
+            "elevasi_pos" => self.elevasi_pos.as_deref(),
+            "koordinat_geografis" => self.koordinat_geografis.as_deref(),
+            "pelaksana" => self.pelaksana.as_deref(),
+            "laboratorium" => self.laboratorium.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Akses tulis field metadata teks berdasarkan nama field (dipakai oleh template lab).
+    /// Tanggal & waktu sampling tidak termasuk karena bertipe tanggal/jam.
+    pub fn metadata_mut(&mut self, field: &str) -> Option<&mut Option<String>> {
+        match field {
+            "nama_pos" => Some(&mut self.nama_pos),
+            "das" => Some(&mut self.das),
+            "wilayah_sungai" => Some(&mut self.wilayah_sungai),
+            "provinsi" => Some(&mut self.provinsi),
+            "kabupaten" => Some(&mut self.kabupaten),
+            "kecamatan" => Some(&mut self.kecamatan),
+            "desa" => Some(&mut self.desa),
+            "sungai" => Some(&mut self.sungai),
+            "elevasi_pos" => Some(&mut self.elevasi_pos),
+            "koordinat_geografis" => Some(&mut self.koordinat_geografis),
+            "pelaksana" => Some(&mut self.pelaksana),
+            "laboratorium" => Some(&mut self.laboratorium),
+            _ => None,
+        }
+    }
+}
@@ -1 +1,12 @@
-pub mod kualitas_air;
\ No newline at end of file
+pub mod kualitas_air;
+pub mod baku_mutu;
+pub mod hasil_perhitungan;
+pub mod suhu_alamiah;
+pub mod pos;
+pub mod import_pdf;
+pub mod template_lab;
+pub mod nilai_tersensor;
+pub mod ekspor_csv;
+pub mod impor_tabel;
+pub mod query_kualitas_air;
+pub mod ekspor_xlsx;
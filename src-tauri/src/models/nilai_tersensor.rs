@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+/// Kualifier hasil uji di luar rentang ukur laboratorium
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "TEXT")]
+pub enum Kualifier {
+    #[serde(rename = "<")]
+    #[sqlx(rename = "<")]
+    KurangDari, // Di bawah batas deteksi (LOD)
+    #[serde(rename = ">")]
+    #[sqlx(rename = ">")]
+    LebihDari,  // Di atas batas ukur (misal Coliform > 2400)
+}
+
+/// Satu nilai tersensor pada sampel: nilai parameter di record = batas deteksi
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NilaiTersensor {
+    pub parameter: String, // Nama field di KualitasAirRecord
+    pub kualifier: Kualifier,
+    pub batas_deteksi: f64,
+}
+
+/// Kebijakan substitusi nilai tersensor dalam perhitungan IP / STORET
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "TEXT")]
+pub enum KebijakanSensor {
+    #[default]
+    #[serde(rename = "LOD")]
+    #[sqlx(rename = "LOD")]
+    Lod,         // Pakai batas deteksi apa adanya
+    #[serde(rename = "LOD/2")]
+    #[sqlx(rename = "LOD/2")]
+    SetengahLod, // Setengah batas deteksi
+    #[serde(rename = "Nol")]
+    #[sqlx(rename = "Nol")]
+    Nol,         // Dianggap nol
+    #[serde(rename = "Abaikan")]
+    #[sqlx(rename = "Abaikan")]
+    Abaikan,     // Parameter tidak ikut dihitung
+}
+
+impl Kualifier {
+    pub fn simbol(&self) -> &'static str {
+        match self {
+            Kualifier::KurangDari => "<",
+            Kualifier::LebihDari => ">",
+        }
+    }
+}
+
+impl KebijakanSensor {
+    pub fn label(&self) -> &'static str {
+        match self {
+            KebijakanSensor::Lod => "LOD",
+            KebijakanSensor::SetengahLod => "LOD/2",
+            KebijakanSensor::Nol => "Nol",
+            KebijakanSensor::Abaikan => "Abaikan",
+        }
+    }
+}
+
+impl NilaiTersensor {
+    /// Nilai pengganti sesuai kebijakan (None = parameter tidak dihitung).
+    /// LOD/2 dan Nol hanya berlaku untuk "<"; nilai ">" tetap memakai batas ukurnya.
+    pub fn substitusi(&self, kebijakan: KebijakanSensor) -> Option<f64> {
+        match (kebijakan, self.kualifier) {
+            (KebijakanSensor::Abaikan, _) => None,
+            (_, Kualifier::LebihDari) | (KebijakanSensor::Lod, _) => Some(self.batas_deteksi),
+            (KebijakanSensor::SetengahLod, Kualifier::KurangDari) => Some(self.batas_deteksi / 2.0),
+            (KebijakanSensor::Nol, Kualifier::KurangDari) => Some(0.0),
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::NaiveDateTime;
+use crate::models::baku_mutu::BakuMutuClass;
+
+/// Pos pemantauan kualitas air (registry, dirujuk oleh kualitas_air.pos_id)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Pos {
+    pub id: Option<i64>,
+    pub nama_pos: String,
+    pub das: Option<String>,
+    pub wilayah_sungai: Option<String>,
+    pub provinsi: Option<String>,
+    pub kabupaten: Option<String>,
+    pub kecamatan: Option<String>,
+    pub desa: Option<String>,
+    pub sungai: Option<String>,
+    pub elevasi_pos: Option<String>,
+    pub koordinat_geografis: Option<String>,
+    pub kelas_baku_mutu: Option<BakuMutuClass>, // Kelas peruntukan default pos (PP 22/2021)
+    pub created_at: Option<NaiveDateTime>,
+}
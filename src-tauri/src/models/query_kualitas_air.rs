@@ -0,0 +1,61 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use crate::models::kualitas_air::KualitasAirRecord;
+
+/// Filter daftar data kualitas air. Field kosong (None / "") tidak membatasi hasil.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FilterKualitasAir {
+    pub pos_id: Option<i64>,
+    pub nama_pos: Option<String>,
+    pub das: Option<String>,
+    pub kabupaten: Option<String>,
+    pub tanggal_awal: Option<NaiveDate>,  // Inklusif
+    pub tanggal_akhir: Option<NaiveDate>, // Inklusif
+    pub status_ip: Option<String>,        // Contoh: "Cemar Ringan"
+    pub kelas_storet: Option<String>,     // "A" / "B" / "C" / "D"
+    pub cari: Option<String>,             // Teks bebas: pos, DAS, sungai, wilayah, lab, pelaksana
+}
+
+/// Arah urutan
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArahUrutan {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// Permintaan satu halaman data: filter + urutan + paginasi
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryKualitasAir {
+    #[serde(default)]
+    pub filter: FilterKualitasAir,
+    #[serde(default)]
+    pub urut: Option<String>, // Nama kolom, default tanggal_sampling
+    #[serde(default)]
+    pub arah: ArahUrutan,
+    #[serde(default = "halaman_default")]
+    pub halaman: u32, // Mulai dari 1
+    #[serde(default = "per_halaman_default")]
+    pub per_halaman: u32,
+}
+
+fn halaman_default() -> u32 { 1 }
+fn per_halaman_default() -> u32 { 50 }
+
+/// Jumlah data per status IP (seluruh hasil filter, bukan hanya halaman ini)
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct JumlahStatus {
+    pub status: Option<String>,
+    pub jumlah: i64,
+}
+
+/// Satu halaman hasil query
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HalamanKualitasAir {
+    pub data: Vec<KualitasAirRecord>,
+    pub total: i64, // Jumlah seluruh data yang lolos filter
+    pub halaman: u32,
+    pub per_halaman: u32,
+    pub per_status_ip: Vec<JumlahStatus>,
+}
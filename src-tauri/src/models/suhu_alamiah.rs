@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Suhu alamiah pos pemantauan, acuan baku mutu Temperatur (Deviasi 3)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SuhuAlamiah {
+    pub id: Option<i64>,
+    pub pos_id: i64,
+    pub bulan: i32, // 1-12 = khusus bulan tersebut, 0 = berlaku sepanjang tahun
+    pub suhu: f64,  // °C
+}
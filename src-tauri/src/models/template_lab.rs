@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+/// Profil format laporan satu laboratorium untuk ekstraksi PDF.
+/// Disimpan sebagai file JSON sehingga format lab baru bisa ditambahkan tanpa kompilasi ulang.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateLab {
+    pub nama: String,
+    #[serde(default)]
+    pub keterangan: Option<String>,
+
+    // Teks penanda untuk deteksi otomatis (misal nama lab / kop surat).
+    // Template dengan penanda terbanyak yang ditemukan di PDF yang dipakai.
+    #[serde(default)]
+    pub deteksi: Vec<String>,
+
+    // Penanda kolom metode uji, misal "SNI" / "APHA" (tidak peka huruf besar/kecil)
+    #[serde(default = "penanda_metode_default")]
+    pub penanda_metode: Vec<String>,
+
+    // Posisi token nilai dihitung mundur dari penanda metode (1 = tepat sebelum penanda)
+    #[serde(default = "posisi_nilai_default")]
+    pub posisi_nilai: usize,
+
+    // Posisi token satuan dihitung mundur dari penanda metode (None = tidak ada kolom satuan)
+    #[serde(default)]
+    pub posisi_satuan: Option<usize>,
+
+    #[serde(default)]
+    pub header: Vec<PolaHeader>,
+
+    // Baris judul kolom untuk laporan multi-sampel (satu kolom per titik / tanggal sampling).
+    // Grup `nilai` memuat seluruh kolom, dipisahkan minimal dua spasi.
+    #[serde(default)]
+    pub header_sampel: Vec<PolaHeader>,
+
+    #[serde(default)]
+    pub parameter: Vec<AliasParameter>,
+}
+
+/// Pola regex untuk satu field metadata header.
+/// Nilai diambil dari grup bernama `nilai` (atau seluruh kecocokan jika grup tidak ada).
+/// Untuk field yang sama, pola yang ditulis lebih dulu lebih diutamakan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolaHeader {
+    pub field: String,
+    pub pola: String,
+    #[serde(default)]
+    pub hapus: Vec<String>, // Teks yang dibuang dari nilai (misal label / titik dua)
+    #[serde(default = "keyakinan_default")]
+    pub keyakinan: f64,
+    #[serde(default)]
+    pub catatan: Option<String>,
+}
+
+/// Alias nama parameter pada baris hasil uji.
+/// Baris cocok jika memuat salah satu kata_kunci, seluruh kata wajib, dan tidak satu pun kata kecuali.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AliasParameter {
+    pub field: String,
+    pub kata_kunci: Vec<String>,
+    #[serde(default)]
+    pub wajib: Vec<String>,
+    #[serde(default)]
+    pub kecuali: Vec<String>,
+
+    // Kata kunci harus berdiri sendiri (misal "DO" tidak cocok dengan "DOKUMEN")
+    #[serde(default)]
+    pub kata_utuh: bool,
+
+    // Parameter ukur lapangan: baris tanpa penanda metode tetap dibaca
+    // (angka pertama setelah kata kunci)
+    #[serde(default)]
+    pub in_situ: bool,
+}
+
+fn penanda_metode_default() -> Vec<String> {
+    vec!["SNI".to_string()]
+}
+
+fn posisi_nilai_default() -> usize {
+    1
+}
+
+fn keyakinan_default() -> f64 {
+    0.9
+}
@@ -0,0 +1,151 @@
+use crate::models::baku_mutu::{BakuMutuClass, BakuMutuSet, BatasParameter, LimitType};
+use crate::models::kualitas_air::NAMA_PARAMETER;
+use sqlx::SqlitePool;
+
+const RUJUKAN_PP22: &str = "PP 22/2021 Lampiran VI";
+
+// Helper pembentuk baris baku mutu bawaan
+fn batas(parameter: &str, jenis: LimitType, bawah: Option<f64>, atas: Option<f64>, satuan: &str) -> BatasParameter {
+    BatasParameter {
+        parameter: parameter.to_string(),
+        jenis,
+        batas_bawah: bawah,
+        batas_atas: atas,
+        satuan: if satuan.is_empty() { None } else { Some(satuan.to_string()) },
+        rujukan: Some(RUJUKAN_PP22.to_string()),
+    }
+}
+
+/// Tabel Baku Mutu Air Sungai per Kelas (PP 22 Tahun 2021, Lampiran VI)
+/// Parameter yang bertanda "-" di Lampiran (tidak dipersyaratkan) tidak dimasukkan,
+/// sehingga tidak ikut dihitung dalam IP.
+pub fn standar_kelas(kelas: BakuMutuClass) -> Vec<BatasParameter> {
+    // Urutan nilai: [Kelas I, Kelas II, Kelas III, Kelas IV]. None = tidak dipersyaratkan.
+    let idx = match kelas {
+        BakuMutuClass::KelasI => 0,
+        BakuMutuClass::KelasII => 1,
+        BakuMutuClass::KelasIII => 2,
+        BakuMutuClass::KelasIV => 3,
+    };
+
+    let tabel_max: [(&str, &str, [Option<f64>; 4]); 22] = [
+        // -- Fisika --
+        ("tds", "mg/L", [Some(1000.0), Some(1000.0), Some(1000.0), Some(2000.0)]),
+        ("tss", "mg/L", [Some(40.0), Some(50.0), Some(100.0), Some(400.0)]),
+        ("warna", "Pt-Co", [Some(15.0), Some(50.0), Some(100.0), None]),
+
+        // -- Nutrient --
+        ("amoniak", "mg/L", [Some(0.1), Some(0.2), Some(0.5), None]),
+        ("nitrat", "mg/L", [Some(10.0), Some(10.0), Some(20.0), Some(20.0)]),
+        ("nitrit", "mg/L", [Some(0.06), Some(0.06), Some(0.06), None]),
+        ("fosfat", "mg/L", [Some(0.2), Some(0.2), Some(1.0), None]),
+        ("deterjen", "mg/L", [Some(0.2), Some(0.2), Some(0.2), None]),
+
+        // -- Logam Berat --
+        ("arsen", "mg/L", [Some(0.05), Some(0.05), Some(0.05), Some(0.1)]),
+        ("besi", "mg/L", [Some(0.3), None, None, None]),
+        ("mangan", "mg/L", [Some(0.4), Some(0.4), Some(0.4), None]),
+        ("tembaga", "mg/L", [Some(0.02), Some(0.02), Some(0.02), Some(0.2)]),
+        ("merkuri", "mg/L", [Some(0.001), Some(0.002), Some(0.002), Some(0.005)]),
+
+        // -- Anorganik Lain --
+        ("sianida", "mg/L", [Some(0.02), Some(0.02), Some(0.02), None]),
+        ("fluorida", "mg/L", [Some(1.0), Some(1.5), Some(1.5), None]),
+        ("klorida", "mg/L", [Some(300.0), Some(300.0), Some(300.0), Some(600.0)]),
+        ("belerang", "mg/L", [Some(0.002), Some(0.002), Some(0.002), None]),
+
+        // -- Organik --
+        ("cod", "mg/L", [Some(10.0), Some(25.0), Some(40.0), Some(80.0)]),
+        ("bod", "mg/L", [Some(2.0), Some(3.0), Some(6.0), Some(12.0)]),
+        ("minyak_dan_lemak", "mg/L", [Some(1.0), Some(1.0), Some(1.0), Some(10.0)]),
+        ("fenol", "mg/L", [Some(0.002), Some(0.005), Some(0.01), Some(0.02)]),
+
+        // -- Mikrobiologi --
+        ("total_coliform", "MPN/100mL", [Some(1000.0), Some(5000.0), Some(10000.0), Some(10000.0)]),
+    ];
+
+    let mut hasil: Vec<BatasParameter> = tabel_max.iter()
+        .filter_map(|(parameter, satuan, nilai)| {
+            nilai[idx].map(|v| batas(parameter, LimitType::Max, None, Some(v), satuan))
+        })
+        .collect();
+
+    // -- Parameter Khusus --
+    hasil.push(batas("temperatur", LimitType::Deviasi, None, Some(3.0), "°C"));
+    hasil.push(batas("ph", LimitType::Range, Some(6.0), Some(9.0), ""));
+    hasil.push(batas("oksigen", LimitType::Min, Some([6.0, 4.0, 3.0, 1.0][idx]), None, "mg/L"));
+
+    hasil
+}
+
+/// Ambil satu set baku mutu kustom beserta seluruh parameternya dari database
+pub async fn load_set(pool: &SqlitePool, id: i64) -> Result<BakuMutuSet, String> {
+    let mut set = sqlx::query_as::<_, BakuMutuSet>("SELECT * FROM baku_mutu_set WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Gagal mengambil baku mutu: {}", e))?
+        .ok_or(format!("Baku mutu dengan ID {} tidak ditemukan", id))?;
+
+    set.parameter = sqlx::query_as::<_, BatasParameter>("SELECT * FROM baku_mutu WHERE set_id = ? ORDER BY id")
+        .bind(id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Gagal mengambil parameter baku mutu: {}", e))?;
+
+    Ok(set)
+}
+
+/// Tentukan tabel baku mutu yang dipakai perhitungan:
+/// set kustom (jika ada ID) atau tabel kelas bawaan.
+pub async fn resolve(pool: &SqlitePool, kelas: BakuMutuClass, set_id: Option<i64>) -> Result<Vec<BatasParameter>, String> {
+    match set_id {
+        Some(id) => Ok(load_set(pool, id).await?.parameter),
+        None => Ok(standar_kelas(kelas)),
+    }
+}
+
+/// Validasi isi set baku mutu sebelum disimpan
+pub fn validate(set: &BakuMutuSet) -> Result<(), String> {
+    if set.nama.trim().is_empty() {
+        return Err("Nama baku mutu wajib diisi".to_string());
+    }
+
+    let mut terpakai = std::collections::HashSet::new();
+    for batas in &set.parameter {
+        if !NAMA_PARAMETER.contains(&batas.parameter.as_str()) {
+            return Err(format!("Parameter tidak dikenal: {}", batas.parameter));
+        }
+        if !terpakai.insert(batas.parameter.as_str()) {
+            return Err(format!("Parameter {} tercantum lebih dari sekali", batas.parameter));
+        }
+
+        let lengkap = match batas.jenis {
+            LimitType::Max | LimitType::Deviasi => batas.batas_atas.is_some(),
+            LimitType::Min => batas.batas_bawah.is_some(),
+            LimitType::Range => batas.batas_bawah.is_some() && batas.batas_atas.is_some(),
+        };
+        if !lengkap {
+            return Err(format!("Batas untuk parameter {} ({:?}) belum lengkap", batas.parameter, batas.jenis));
+        }
+
+        // Batas menjadi pembagi rasio C/L di perhitungan IP
+        if batas.batas_atas.is_some_and(|atas| atas <= 0.0) {
+            return Err(format!("Batas atas parameter {} harus lebih dari 0", batas.parameter));
+        }
+        match (batas.jenis, batas.batas_bawah, batas.batas_atas) {
+            (LimitType::Min, Some(bawah), _) if bawah <= 0.0 => {
+                return Err(format!("Batas bawah parameter {} harus lebih dari 0", batas.parameter));
+            }
+            (LimitType::Range, Some(bawah), _) if bawah < 0.0 => {
+                return Err(format!("Batas bawah parameter {} tidak boleh negatif", batas.parameter));
+            }
+            (LimitType::Range, Some(bawah), Some(atas)) if bawah > atas => {
+                return Err(format!("Batas bawah parameter {} melebihi batas atasnya", batas.parameter));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
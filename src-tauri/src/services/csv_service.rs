@@ -1,25 +1,150 @@
-use crate::models::kualitas_air::KualitasAirRecord;
+use crate::models::ekspor_csv::{DesimalCsv, KolomEkspor, OpsiEksporCsv, PemisahCsv};
+use crate::models::kualitas_air::{label_parameter, satuan_parameter, KualitasAirRecord, NAMA_PARAMETER};
 use std::fs::File;
+use std::io::Write;
 
-pub fn export_to_csv(data: &Vec<KualitasAirRecord>, file_path: &str) -> Result<(), String>
+// Kolom non-parameter yang bisa diexport: (kunci, judul). Parameter disisipkan di antara
+// KOLOM_IDENTITAS dan KOLOM_HASIL dengan judul dari LABEL_PARAMETER + satuan baku.
+const KOLOM_IDENTITAS: &[(&str, &str)] = &[
+    ("id", "ID"),
+    ("tanggal_sampling", "Tanggal Sampling"),
+    ("waktu_sampling", "Waktu Sampling"),
+    ("tahun", "Tahun"),
+    ("nama_pos", "Nama Pos"),
+    ("das", "DAS"),
+    ("wilayah_sungai", "Wilayah Sungai"),
+    ("sungai", "Sungai"),
+    ("provinsi", "Provinsi"),
+    ("kabupaten", "Kabupaten/Kota"),
+    ("kecamatan", "Kecamatan"),
+    ("desa", "Desa"),
+    ("elevasi_pos", "Elevasi Pos"),
+    ("koordinat_geografis", "Koordinat Geografis"),
+    ("kelas_baku_mutu", "Kelas Baku Mutu Pos"),
+    ("pelaksana", "Pelaksana"),
+    ("laboratorium", "Laboratorium"),
+];
+const KOLOM_HASIL: &[(&str, &str)] = &[
+    ("nilai_ip", "Indeks Pencemaran (IP)"),
+    ("status_ip", "Status IP"),
+    ("kelas_ip", "Kelas Baku Mutu IP"),
+    ("nilai_storet", "Skor STORET"),
+    ("status_storet", "Status STORET"),
+    ("kebijakan_sensor", "Kebijakan Nilai Tersensor"),
+    ("tersensor", "Nilai Tersensor"),
+    ("created_at", "Waktu Input"),
+];
+
+/// Judul kolom di file export, misal "Besi (Fe) [mg/L]" (None jika kunci tidak dikenal)
+pub fn judul_kolom(kunci: &str) -> Option<String> {
+    if let Some(label) = label_parameter(kunci) {
+        return Some(match satuan_parameter(kunci) {
+            Some(satuan) if satuan != "-" => format!("{} [{}]", label, satuan),
+            _ => label.to_string(),
+        });
+    }
+    KOLOM_IDENTITAS.iter().chain(KOLOM_HASIL)
+        .find(|(k, _)| *k == kunci)
+        .map(|(_, judul)| judul.to_string())
+}
+
+/// Seluruh kolom export sesuai urutan bawaan
+pub fn daftar_kolom() -> Vec<KolomEkspor> {
+    KOLOM_IDENTITAS.iter().map(|(k, _)| *k)
+        .chain(NAMA_PARAMETER)
+        .chain(KOLOM_HASIL.iter().map(|(k, _)| *k))
+        .filter_map(|kunci| Some(KolomEkspor { kunci: kunci.to_string(), judul: judul_kolom(kunci)? }))
+        .collect()
+}
+
+// Angka dengan pemisah desimal pilihan (tanpa notasi eksponen)
+fn angka(nilai: f64, desimal: DesimalCsv) -> String {
+    let teks = nilai.to_string();
+    match desimal {
+        DesimalCsv::Titik => teks,
+        DesimalCsv::Koma => teks.replace('.', ","),
+    }
+}
+
+/// Isi satu sel sebagai teks (kosong jika nilai tidak ada); dipakai juga oleh export Excel
+pub(crate) fn sel(record: &KualitasAirRecord, kunci: &str, desimal: DesimalCsv) -> String {
+    let num = |nilai: Option<f64>| nilai.map(|n| angka(n, desimal)).unwrap_or_default();
+    let teks = |nilai: Option<&str>| nilai.unwrap_or_default().to_string();
+
+    if NAMA_PARAMETER.contains(&kunci) {
+        return num(record.nilai_parameter(kunci));
+    }
+    match kunci {
+        "id" => record.id.map(|id| id.to_string()).unwrap_or_default(),
+        "tanggal_sampling" => record.tanggal_sampling.map(|t| t.format("%Y-%m-%d").to_string()).unwrap_or_default(),
+        "waktu_sampling" => record.waktu_sampling.map(|w| w.format("%H:%M").to_string()).unwrap_or_default(),
+        "tahun" => record.tahun.map(|t| t.to_string()).unwrap_or_default(),
+        "kelas_baku_mutu" => teks(record.kelas_baku_mutu.map(|k| k.label())),
+        "nilai_ip" => num(record.nilai_ip),
+        "status_ip" => teks(record.status_ip.as_deref()),
+        "kelas_ip" => teks(record.kelas_ip.map(|k| k.label())),
+        "nilai_storet" => num(record.nilai_storet),
+        "status_storet" => teks(record.status_storet.as_deref()),
+        "kebijakan_sensor" => teks(record.kebijakan_sensor.map(|k| k.label())),
+        // Misal "Fenol <0.002; Total Coliform >2400"
+        "tersensor" => record.tersensor.iter()
+            .map(|t| format!(
+                "{} {}{}",
+                label_parameter(&t.parameter).unwrap_or(&t.parameter),
+                t.kualifier.simbol(),
+                angka(t.batas_deteksi, desimal)
+            ))
+            .collect::<Vec<_>>()
+            .join("; "),
+        "created_at" => record.created_at.map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string()).unwrap_or_default(),
+        metadata => teks(record.metadata(metadata)),
+    }
+}
+
+pub fn export_to_csv(data: &[KualitasAirRecord], opsi: &OpsiEksporCsv, file_path: &str) -> Result<(), String>
 {
-    // 1. coba buat file di path yang diminta
-    let file = File::create(file_path)
+    // Angka "1,5" di file berpemisah koma tetap terbaca, tapi membingungkan saat dibuka manual
+    if opsi.pemisah == PemisahCsv::Koma && opsi.desimal == DesimalCsv::Koma {
+        return Err("Pemisah kolom dan pemisah desimal tidak boleh sama-sama koma".to_string());
+    }
+
+    // 1. tentukan kolom & judulnya (urutan sesuai pilihan pengguna)
+    let kolom: Vec<String> = if opsi.kolom.is_empty() {
+        daftar_kolom().into_iter().map(|k| k.kunci).collect()
+    } else {
+        opsi.kolom.clone()
+    };
+    let judul: Vec<String> = kolom.iter()
+        .map(|k| judul_kolom(k).ok_or_else(|| format!("Kolom export tidak dikenal: {}", k)))
+        .collect::<Result<_, _>>()?;
+
+    // 2. coba buat file di path yang diminta
+    let mut file = File::create(file_path)
         .map_err(|e| format!("Gagal membuat file: {}", e))?;
 
-    // 2. init csv writer
-    let mut wtr = csv::Writer::from_writer(file);
+    // BOM UTF-8 agar Excel membaca "°C" / "µS/cm" dengan benar
+    file.write_all(b"\xEF\xBB\xBF")
+        .map_err(|e| format!("Gagal menulis file: {}", e))?;
+
+    // 3. init csv writer
+    let mut wtr = csv::WriterBuilder::new()
+        .delimiter(opsi.pemisah.byte())
+        .from_writer(file);
+
+    // 4. tulis header lalu setiap record
+    wtr.write_record(&judul)
+        .map_err(|e| format!("Gagal menulis header: {}", e))?;
 
-    // 3. loop dan tulis setiap record
     for record in data
     {
-        wtr.serialize(record)
+        let baris: Vec<String> = kolom.iter().map(|k| sel(record, k, opsi.desimal)).collect();
+        wtr.write_record(&baris)
             .map_err(|e| format!("Gagal menulis baris data: {}", e))?;
     }
 
-    // 4. flush buffer untuk memastikan semua data tertulis
+    // 5. flush buffer untuk memastikan semua data tertulis
     wtr.flush()
         .map_err(|e| format!("Gagal menyimpan file .csv: {}", e))?;
 
     Ok(())
-}
\ No newline at end of file
+}
@@ -1,10 +1,14 @@
-use sqlx::{sqlite::SqliteQueryResult, Sqlite, SqlitePool, migrate::MigrateDatabase};
+use sqlx::{Sqlite, SqlitePool, migrate::MigrateDatabase};
 use std::fs;
+use std::path::Path;
 use tauri::{Manager, AppHandle};
 
 // Nama file database
 const DB_FILENAME: &str = "data_hidrologi.db";
 
+// Folder backup (di dalam AppLocalData) yang diisi sebelum setiap migrasi
+const BACKUP_DIRNAME: &str = "backup";
+
 /// Inisialisasi Database: Cek file, buat jika tidak ada, dan lakukan migrasi tabel
 pub async fn init_db(app_handle: &AppHandle) -> Result<SqlitePool, String> {
     // 1. Tentukan lokasi penyimpanan (AppLocalData)
@@ -30,18 +34,39 @@ pub async fn init_db(app_handle: &AppHandle) -> Result<SqlitePool, String> {
     let pool = SqlitePool::connect(&db_url).await
         .map_err(|e| format!("Gagal koneksi ke database: {}", e))?;
 
-    // 4. Jalankan Migrasi (Buat Tabel)
-    create_tables(&pool).await
-        .map_err(|e| format!("Gagal membuat tabel: {}", e))?;
+    // 4. Jalankan Migrasi Berversi (database lama di-upgrade di tempat)
+    run_migrations(&pool, &app_dir.join(BACKUP_DIRNAME)).await?;
 
     println!("Database terhubung di: {:?}", db_path);
     Ok(pool)
 }
 
-/// Fungsi untuk membuat struktur tabel
-async fn create_tables(pool: &SqlitePool) -> Result<SqliteQueryResult, sqlx::Error> {
-    // Query ini HARUS cocok dengan struct KualitasAirRecord di models/kualitas_air.rs
-    let query = "
+// =====================================================================
+// MIGRASI SKEMA
+// =====================================================================
+// Setiap perubahan skema WAJIB ditambahkan sebagai versi baru di MIGRATIONS
+// (jangan mengubah migrasi yang sudah dirilis). Versi yang sudah diterapkan
+// dicatat di tabel schema_version.
+
+// Satu langkah di dalam migrasi
+enum Langkah {
+    Sql(&'static str),
+    // Idempoten: aman untuk database yang kolomnya sudah ditambahkan manual
+    TambahKolom { tabel: &'static str, kolom: &'static str, tipe: &'static str },
+}
+
+struct Migrasi {
+    versi: i64,
+    nama: &'static str,
+    langkah: &'static [Langkah],
+}
+
+const MIGRATIONS: &[Migrasi] = &[
+    Migrasi {
+        versi: 1,
+        nama: "skema awal kualitas_air",
+        // Query ini HARUS cocok dengan struct KualitasAirRecord versi awal
+        langkah: &[Langkah::Sql("
     CREATE TABLE IF NOT EXISTS kualitas_air (
         id INTEGER PRIMARY KEY AUTOINCREMENT,
         
@@ -117,7 +142,330 @@ async fn create_tables(pool: &SqlitePool) -> Result<SqliteQueryResult, sqlx::Err
         -- System
         created_at DATETIME DEFAULT CURRENT_TIMESTAMP
     );
-    ";
+    ")],
+    },
+    Migrasi {
+        versi: 2,
+        nama: "kelas baku mutu per pos & kelas yang dipakai IP",
+        langkah: &[
+            Langkah::TambahKolom { tabel: "kualitas_air", kolom: "kelas_baku_mutu", tipe: "TEXT" },
+            Langkah::TambahKolom { tabel: "kualitas_air", kolom: "kelas_ip", tipe: "TEXT" },
+            Langkah::TambahKolom { tabel: "kualitas_air", kolom: "baku_mutu_id", tipe: "INTEGER" },
+        ],
+    },
+    Migrasi {
+        versi: 3,
+        nama: "set baku mutu kustom",
+        langkah: &[
+            // Set baku mutu kustom (misal Pergub yang memperketat PP 22/2021)
+            Langkah::Sql("
+    CREATE TABLE IF NOT EXISTS baku_mutu_set (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        nama TEXT NOT NULL UNIQUE,
+        rujukan TEXT,
+        keterangan TEXT,
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+            "),
+            // Baris batas per parameter, HARUS cocok dengan struct BatasParameter di models/baku_mutu.rs
+            Langkah::Sql("
+    CREATE TABLE IF NOT EXISTS baku_mutu (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        set_id INTEGER NOT NULL REFERENCES baku_mutu_set(id) ON DELETE CASCADE,
+        parameter TEXT NOT NULL,
+        jenis TEXT NOT NULL,        -- Max / Min / Range / Deviasi
+        batas_bawah REAL,
+        batas_atas REAL,
+        satuan TEXT,
+        rujukan TEXT,
+        UNIQUE (set_id, parameter)
+    );
+            "),
+        ],
+    },
+    Migrasi {
+        versi: 4,
+        nama: "suhu alamiah per pos",
+        // Suhu alamiah per pos (bulan 0 = sepanjang tahun), acuan Temperatur Deviasi 3
+        langkah: &[Langkah::Sql("
+    CREATE TABLE IF NOT EXISTS suhu_alamiah (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        nama_pos TEXT NOT NULL,
+        bulan INTEGER NOT NULL DEFAULT 0 CHECK (bulan BETWEEN 0 AND 12),
+        suhu REAL NOT NULL,
+        UNIQUE (nama_pos, bulan)
+    );
+        ")],
+    },
+    Migrasi {
+        versi: 5,
+        nama: "registry pos pemantauan",
+        langkah: &[
+            // Registry pos, HARUS cocok dengan struct Pos di models/pos.rs
+            Langkah::Sql("
+    CREATE TABLE IF NOT EXISTS pos (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        nama_pos TEXT NOT NULL,
+        das TEXT,
+        wilayah_sungai TEXT,
+        provinsi TEXT,
+        kabupaten TEXT,
+        kecamatan TEXT,
+        desa TEXT,
+        sungai TEXT,
+        elevasi_pos TEXT,
+        koordinat_geografis TEXT,
+        kelas_baku_mutu TEXT,
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        UNIQUE (nama_pos, koordinat_geografis)
+    );
+            "),
+            // Deduplikasi: satu pos per (nama, koordinat), metadata diambil dari sampel terbaru
+            Langkah::Sql("
+    INSERT INTO pos (
+        nama_pos, das, wilayah_sungai, provinsi, kabupaten, kecamatan, desa, sungai,
+        elevasi_pos, koordinat_geografis, kelas_baku_mutu
+    )
+    SELECT
+        COALESCE(NULLIF(TRIM(nama_pos), ''), 'Tanpa Nama'), das, wilayah_sungai, provinsi, kabupaten,
+        kecamatan, desa, sungai, elevasi_pos, NULLIF(TRIM(koordinat_geografis), ''), kelas_baku_mutu
+    FROM kualitas_air
+    WHERE id IN (
+        SELECT MAX(id) FROM kualitas_air
+        GROUP BY COALESCE(NULLIF(TRIM(nama_pos), ''), 'Tanpa Nama'), NULLIF(TRIM(koordinat_geografis), '')
+    );
+            "),
+            Langkah::TambahKolom { tabel: "kualitas_air", kolom: "pos_id", tipe: "INTEGER REFERENCES pos(id)" },
+            Langkah::Sql("
+    UPDATE kualitas_air SET pos_id = (
+        SELECT p.id FROM pos p
+        WHERE p.nama_pos = COALESCE(NULLIF(TRIM(kualitas_air.nama_pos), ''), 'Tanpa Nama')
+          AND p.koordinat_geografis IS NULLIF(TRIM(kualitas_air.koordinat_geografis), '')
+    );
+            "),
+            // Suhu alamiah dirujuk lewat pos_id (bukan nama bebas) agar tidak lepas saat pos diganti namanya.
+            // Nama pos yang belum punya sampel didaftarkan sebagai pos baru; jika satu nama dipakai
+            // beberapa pos (koordinat berbeda), setiap pos mendapat salinan suhu alamiahnya.
+            Langkah::Sql("
+    INSERT INTO pos (nama_pos)
+    SELECT MIN(TRIM(s.nama_pos)) FROM suhu_alamiah s
+    WHERE NOT EXISTS (SELECT 1 FROM pos p WHERE p.nama_pos = TRIM(s.nama_pos) COLLATE NOCASE)
+    GROUP BY TRIM(s.nama_pos) COLLATE NOCASE;
+            "),
+            Langkah::Sql("
+    CREATE TABLE suhu_alamiah_baru (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        pos_id INTEGER NOT NULL REFERENCES pos(id) ON DELETE CASCADE,
+        bulan INTEGER NOT NULL DEFAULT 0 CHECK (bulan BETWEEN 0 AND 12),
+        suhu REAL NOT NULL,
+        UNIQUE (pos_id, bulan)
+    );
+            "),
+            Langkah::Sql("
+    INSERT OR IGNORE INTO suhu_alamiah_baru (pos_id, bulan, suhu)
+    SELECT p.id, s.bulan, s.suhu
+    FROM suhu_alamiah s
+    JOIN pos p ON p.nama_pos = TRIM(s.nama_pos) COLLATE NOCASE
+    ORDER BY s.id;
+            "),
+            Langkah::Sql("DROP TABLE suhu_alamiah;"),
+            Langkah::Sql("ALTER TABLE suhu_alamiah_baru RENAME TO suhu_alamiah;"),
+            // Metadata pos kini hanya disimpan di tabel pos
+            Langkah::Sql("ALTER TABLE kualitas_air DROP COLUMN nama_pos;"),
+            Langkah::Sql("ALTER TABLE kualitas_air DROP COLUMN das;"),
+            Langkah::Sql("ALTER TABLE kualitas_air DROP COLUMN wilayah_sungai;"),
+            Langkah::Sql("ALTER TABLE kualitas_air DROP COLUMN provinsi;"),
+            Langkah::Sql("ALTER TABLE kualitas_air DROP COLUMN kabupaten;"),
+            Langkah::Sql("ALTER TABLE kualitas_air DROP COLUMN kecamatan;"),
+            Langkah::Sql("ALTER TABLE kualitas_air DROP COLUMN desa;"),
+            Langkah::Sql("ALTER TABLE kualitas_air DROP COLUMN sungai;"),
+            Langkah::Sql("ALTER TABLE kualitas_air DROP COLUMN elevasi_pos;"),
+            Langkah::Sql("ALTER TABLE kualitas_air DROP COLUMN koordinat_geografis;"),
+            Langkah::Sql("ALTER TABLE kualitas_air DROP COLUMN kelas_baku_mutu;"),
+            Langkah::Sql("CREATE INDEX IF NOT EXISTS idx_kualitas_air_pos ON kualitas_air (pos_id);"),
+            // View baca: sampel + metadata pos, kolomnya HARUS cocok dengan struct KualitasAirRecord
+            Langkah::Sql("
+    CREATE VIEW IF NOT EXISTS v_kualitas_air AS
+    SELECT
+        k.*,
+        p.nama_pos, p.das, p.wilayah_sungai, p.provinsi, p.kabupaten, p.kecamatan,
+        p.desa, p.sungai, p.elevasi_pos, p.koordinat_geografis, p.kelas_baku_mutu
+    FROM kualitas_air k
+    LEFT JOIN pos p ON p.id = k.pos_id;
+            "),
+        ],
+    },
+    Migrasi {
+        versi: 6,
+        nama: "nilai tersensor & kebijakan substitusi",
+        langkah: &[
+            Langkah::TambahKolom { tabel: "kualitas_air", kolom: "kebijakan_sensor", tipe: "TEXT" },
+            // Nilai di luar batas deteksi, HARUS cocok dengan struct NilaiTersensor di models/nilai_tersensor.rs
+            // (nilai lama hasil "<x * 0.99" tidak bisa dipulihkan dan tetap tersimpan sebagai angka biasa)
+            Langkah::Sql("
+    CREATE TABLE IF NOT EXISTS nilai_tersensor (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        kualitas_air_id INTEGER NOT NULL REFERENCES kualitas_air(id) ON DELETE CASCADE,
+        parameter TEXT NOT NULL,
+        kualifier TEXT NOT NULL CHECK (kualifier IN ('<', '>')),
+        batas_deteksi REAL NOT NULL,
+        UNIQUE (kualitas_air_id, parameter)
+    );
+            "),
+        ],
+    },
+    Migrasi {
+        versi: 7,
+        nama: "tanggal & waktu sampling format ISO",
+        // Tanggal "DD-MM-YYYY" / "D-MM-YYYY" / "DD/MM/YYYY" -> "YYYY-MM-DD", waktu "HH:MM" -> "HH:MM:SS"
+        // agar bisa diurutkan & difilter langsung di SQL (NaiveDate / NaiveTime di KualitasAirRecord).
+        // Nilai yang tidak bisa dibaca dikosongkan (nilai aslinya tetap ada di backup sebelum migrasi).
+        langkah: &[
+            Langkah::Sql("
+    UPDATE kualitas_air
+    SET tanggal_sampling = CASE
+        WHEN t GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9]' THEN t
+        WHEN t GLOB '[0-9][0-9]-[0-9][0-9]-[0-9][0-9][0-9][0-9]'
+            THEN substr(t, 7, 4) || '-' || substr(t, 4, 2) || '-' || substr(t, 1, 2)
+        WHEN t GLOB '[0-9]-[0-9][0-9]-[0-9][0-9][0-9][0-9]'
+            THEN substr(t, 6, 4) || '-' || substr(t, 3, 2) || '-0' || substr(t, 1, 1)
+    END
+    FROM (SELECT id AS sid, REPLACE(REPLACE(TRIM(tanggal_sampling), '/', '-'), '.', '-') AS t FROM kualitas_air)
+    WHERE id = sid AND tanggal_sampling IS NOT NULL;
+            "),
+            // Tanggal mustahil (misal 31-02) dinormalisasi SQLite menjadi tanggal lain, jadi ikut dikosongkan
+            Langkah::Sql("UPDATE kualitas_air SET tanggal_sampling = NULL WHERE date(julianday(tanggal_sampling)) IS NOT tanggal_sampling;"),
+            Langkah::Sql("
+    UPDATE kualitas_air
+    SET waktu_sampling = time(CASE WHEN w GLOB '[0-9]:[0-9][0-9]*' THEN '0' || w ELSE w END)
+    FROM (SELECT id AS sid, REPLACE(TRIM(waktu_sampling), '.', ':') AS w FROM kualitas_air)
+    WHERE id = sid AND waktu_sampling IS NOT NULL;
+            "),
+            Langkah::Sql("CREATE INDEX IF NOT EXISTS idx_kualitas_air_tanggal ON kualitas_air(tanggal_sampling, waktu_sampling);"),
+        ],
+    },
+    Migrasi {
+        versi: 8,
+        nama: "indeks filter & urutan daftar kualitas air",
+        // Kolom yang sering difilter / diurutkan di query_kualitas_air.
+        // Filter DAS & kabupaten tidak peka huruf besar-kecil, jadi indeksnya COLLATE NOCASE.
+        langkah: &[
+            Langkah::Sql("CREATE INDEX IF NOT EXISTS idx_kualitas_air_status_ip ON kualitas_air(status_ip);"),
+            Langkah::Sql("CREATE INDEX IF NOT EXISTS idx_kualitas_air_status_storet ON kualitas_air(status_storet);"),
+            Langkah::Sql("CREATE INDEX IF NOT EXISTS idx_kualitas_air_nilai_ip ON kualitas_air(nilai_ip);"),
+            Langkah::Sql("CREATE INDEX IF NOT EXISTS idx_pos_das ON pos(das COLLATE NOCASE);"),
+            Langkah::Sql("CREATE INDEX IF NOT EXISTS idx_pos_kabupaten ON pos(kabupaten COLLATE NOCASE);"),
+        ],
+    },
+];
+
+/// Jalankan seluruh migrasi yang belum diterapkan, masing-masing dalam satu transaksi.
+/// Sebelum setiap migrasi, database lama (yang sudah berisi tabel) dibackup ke `backup_dir`.
+pub(crate) async fn run_migrations(pool: &SqlitePool, backup_dir: &Path) -> Result<(), String> {
+    sqlx::query("
+    CREATE TABLE IF NOT EXISTS schema_version (
+        versi INTEGER PRIMARY KEY,
+        nama TEXT NOT NULL,
+        applied_at DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+    ")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Gagal membuat tabel schema_version: {}", e))?;
+
+    let versi_sekarang: i64 = sqlx::query_scalar("SELECT COALESCE(MAX(versi), 0) FROM schema_version")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Gagal membaca versi skema: {}", e))?;
+
+    // Database baru (belum ada tabel data) tidak perlu dibackup
+    let ada_data = table_exists(pool, "kualitas_air").await
+        .map_err(|e| format!("Gagal memeriksa tabel: {}", e))?;
+
+    for migrasi in MIGRATIONS.iter().filter(|m| m.versi > versi_sekarang) {
+        // 1. Backup
+        if ada_data {
+            backup_database(pool, backup_dir, migrasi.versi).await?;
+        }
+
+        // 2. Terapkan seluruh langkah + catat versi dalam satu transaksi
+        let mut tx = pool.begin().await
+            .map_err(|e| format!("Gagal memulai transaksi migrasi: {}", e))?;
+
+        for langkah in migrasi.langkah {
+            match langkah {
+                Langkah::Sql(sql) => {
+                    sqlx::query(sql).execute(&mut *tx).await
+                        .map_err(|e| format!("Migrasi v{} ({}) gagal: {}", migrasi.versi, migrasi.nama, e))?;
+                },
+                Langkah::TambahKolom { tabel, kolom, tipe } => {
+                    add_column_if_missing(&mut tx, tabel, kolom, tipe).await
+                        .map_err(|e| format!("Migrasi v{} ({}) gagal: {}", migrasi.versi, migrasi.nama, e))?;
+                },
+            }
+        }
+
+        sqlx::query("INSERT INTO schema_version (versi, nama) VALUES (?, ?)")
+            .bind(migrasi.versi)
+            .bind(migrasi.nama)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Gagal mencatat versi skema: {}", e))?;
+
+        tx.commit().await
+            .map_err(|e| format!("Migrasi v{} ({}) gagal: {}", migrasi.versi, migrasi.nama, e))?;
+
+        println!("✅ Migrasi v{} diterapkan: {}", migrasi.versi, migrasi.nama);
+    }
 
-    sqlx::query(query).execute(pool).await
-}
\ No newline at end of file
+    Ok(())
+}
+
+/// Salin database ke folder backup sebelum migrasi (VACUUM INTO menghasilkan salinan yang konsisten)
+async fn backup_database(pool: &SqlitePool, backup_dir: &Path, versi: i64) -> Result<(), String> {
+    fs::create_dir_all(backup_dir)
+        .map_err(|e| format!("Gagal membuat folder backup: {}", e))?;
+
+    let waktu = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let backup_path = backup_dir.join(format!("data_hidrologi_sebelum_v{}_{}.db", versi, waktu));
+    let backup_str = backup_path.to_str()
+        .ok_or("Path backup tidak valid".to_string())?
+        .replace('\'', "''");
+
+    sqlx::query(&format!("VACUUM INTO '{}'", backup_str))
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Gagal backup database sebelum migrasi v{}: {}", versi, e))?;
+
+    println!("Backup database dibuat: {:?}", backup_path);
+    Ok(())
+}
+
+async fn table_exists(pool: &SqlitePool, table: &str) -> Result<bool, sqlx::Error> {
+    let jumlah: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?")
+        .bind(table)
+        .fetch_one(pool)
+        .await?;
+    Ok(jumlah > 0)
+}
+
+/// Tambah kolom ke tabel jika kolom tersebut belum ada
+async fn add_column_if_missing(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    table: &str,
+    column: &str,
+    tipe: &str
+) -> Result<(), sqlx::Error> {
+    let columns: Vec<String> = sqlx::query_scalar(&format!("SELECT name FROM pragma_table_info('{}')", table))
+        .fetch_all(&mut **tx)
+        .await?;
+
+    if !columns.iter().any(|c| c == column) {
+        sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, tipe))
+            .execute(&mut **tx)
+            .await?;
+        println!("Kolom baru ditambahkan: {}.{}", table, column);
+    }
+
+    Ok(())
+}
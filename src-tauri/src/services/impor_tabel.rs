@@ -0,0 +1,687 @@
+use crate::models::baku_mutu::BakuMutuClass;
+use crate::models::ekspor_csv::{DesimalCsv, KolomEkspor, PemisahCsv};
+use crate::models::impor_tabel::{BarisImpor, DaftarPemetaan, FileImpor, HasilImporTabel, PemetaanImpor};
+use crate::models::kualitas_air::{label_parameter, satuan_parameter, KualitasAirRecord, NAMA_METADATA, NAMA_PARAMETER};
+use crate::models::nilai_tersensor::{Kualifier, NilaiTersensor};
+use crate::services::csv_service::judul_kolom;
+use crate::services::satuan::{self, Konversi};
+use crate::services::tanggal;
+use calamine::{open_workbook_auto, Data, DataType, Reader};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+use sqlx::SqlitePool;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+// Folder pemetaan kolom tersimpan (di dalam AppLocalData), satu file .json per pemetaan
+const PEMETAAN_DIRNAME: &str = "pemetaan_impor";
+
+// Jumlah baris contoh yang dikirim ke frontend saat memilih file
+const JUMLAH_CONTOH: usize = 5;
+
+// Field selain parameter & metadata teks yang bisa diisi dari file
+const FIELD_LAIN: [&str; 2] = ["tahun", "kelas_baku_mutu"];
+
+// Field yang wajib dipetakan: tanpa pos & tanggal, data tidak bisa dikaitkan / diurutkan
+const FIELD_WAJIB: [&str; 2] = ["nama_pos", "tanggal_sampling"];
+
+// Judul kolom singkat yang sering dipakai di spreadsheet lama (bentuk ternormalisasi)
+const ALIAS_JUDUL: &[(&str, &str)] = &[
+    ("tanggal", "tanggal_sampling"), ("tgl", "tanggal_sampling"), ("tglsampling", "tanggal_sampling"),
+    ("waktu", "waktu_sampling"), ("jam", "waktu_sampling"),
+    ("pos", "nama_pos"), ("lokasi", "nama_pos"), ("titiksampling", "nama_pos"),
+    ("kabupatenkota", "kabupaten"), ("kota", "kabupaten"),
+    ("lab", "laboratorium"), ("koordinat", "koordinat_geografis"), ("elevasi", "elevasi_pos"),
+    ("kelasair", "kelas_baku_mutu"), ("kelas", "kelas_baku_mutu"),
+    ("suhu", "temperatur"), ("dhl", "konduktivitas"), ("do", "oksigen"), ("oksigenterlarut", "oksigen"),
+    ("minyakdanlemak", "minyak_dan_lemak"), ("minyaklemak", "minyak_dan_lemak"),
+    ("h2s", "belerang"), ("coliform", "total_coliform"), ("totalcoliform", "total_coliform"),
+];
+
+/// Isi satu sel file sumber
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sel {
+    Kosong,
+    Teks(String),
+    Angka(f64),
+    Tanggal(NaiveDateTime), // Sel tanggal/jam Excel
+}
+
+impl Sel {
+    fn tampil(&self) -> String {
+        match self {
+            Sel::Kosong => String::new(),
+            Sel::Teks(teks) => teks.clone(),
+            Sel::Angka(nilai) => nilai.to_string(),
+            // Sel jam saja (tanggal 1899-12-31) / tanggal saja (jam 00:00) ditampilkan seperlunya
+            Sel::Tanggal(waktu) if waktu.year() < 1900 => waktu.format("%H:%M").to_string(),
+            Sel::Tanggal(waktu) if waktu.time() == NaiveTime::MIN => waktu.format("%Y-%m-%d").to_string(),
+            Sel::Tanggal(waktu) => waktu.format("%Y-%m-%d %H:%M").to_string(),
+        }
+    }
+
+    fn kosong(&self) -> bool {
+        match self {
+            Sel::Kosong => true,
+            Sel::Teks(teks) => teks.trim().is_empty(),
+            _ => false,
+        }
+    }
+}
+
+// Satu baris file: (nomor baris di file, sel)
+type BarisSel = (usize, Vec<Sel>);
+
+/// Isi file setelah baris header
+pub struct Tabel {
+    pub lembar: Vec<String>,
+    pub judul: Vec<String>,
+    pub baris: Vec<BarisSel>,
+}
+
+// Seluruh field yang bisa diisi dari file
+fn semua_field() -> impl Iterator<Item = &'static str> {
+    NAMA_METADATA.into_iter().chain(FIELD_LAIN).chain(NAMA_PARAMETER)
+}
+
+fn field_dikenal(field: &str) -> bool {
+    semua_field().any(|f| f == field)
+}
+
+/// Field yang bisa dipetakan beserta judulnya (untuk pilihan di form pemetaan)
+pub fn daftar_field() -> Vec<KolomEkspor> {
+    semua_field()
+        .filter_map(|kunci| Some(KolomEkspor { kunci: kunci.to_string(), judul: judul_kolom(kunci)? }))
+        .collect()
+}
+
+// ============================================
+// MEMBACA FILE
+// ============================================
+
+/// Baca file CSV / Excel sesuai pengaturan pemetaan (sheet, baris header, pemisah)
+pub fn baca(path: &str, pemetaan: &PemetaanImpor) -> Result<Tabel, String> {
+    if pemetaan.baris_header == 0 {
+        return Err("Baris header dihitung mulai dari 1".to_string());
+    }
+
+    let ekstensi = Path::new(path).extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let (lembar, semua_baris) = match ekstensi.as_str() {
+        "csv" | "txt" => (Vec::new(), baca_csv(path, pemetaan.pemisah)?),
+        "xlsx" | "xlsm" | "xls" | "ods" => baca_excel(path, pemetaan.lembar.as_deref())?,
+        _ => return Err(format!("Format file .{} tidak didukung (gunakan CSV atau Excel)", ekstensi)),
+    };
+
+    let mut baris = semua_baris.into_iter().skip_while(|(nomor, _)| *nomor < pemetaan.baris_header);
+    let Some((_, header)) = baris.next() else {
+        return Err(format!("Baris header ke-{} tidak ditemukan di file", pemetaan.baris_header));
+    };
+
+    // Judul kosong / ganda diberi nama pengganti agar setiap kolom bisa dipilih
+    let mut judul: Vec<String> = Vec::with_capacity(header.len());
+    for (idx, sel) in header.iter().enumerate() {
+        let mut nama = sel.tampil().split_whitespace().collect::<Vec<_>>().join(" ");
+        if nama.is_empty() {
+            nama = format!("Kolom {}", idx + 1);
+        }
+        if judul.contains(&nama) {
+            nama = format!("{} ({})", nama, idx + 1);
+        }
+        judul.push(nama);
+    }
+
+    let baris = baris.filter(|(_, sel)| !sel.iter().all(Sel::kosong)).collect();
+    Ok(Tabel { lembar, judul, baris })
+}
+
+// Pemisah kolom yang paling sering muncul di baris pertama
+fn deteksi_pemisah(teks: &str) -> PemisahCsv {
+    let baris = teks.lines().next().unwrap_or_default();
+    [(PemisahCsv::TitikKoma, ';'), (PemisahCsv::Tab, '\t'), (PemisahCsv::Koma, ',')]
+        .into_iter()
+        .max_by_key(|(_, c)| baris.matches(*c).count())
+        .map(|(pemisah, _)| pemisah)
+        .unwrap_or_default()
+}
+
+fn baca_csv(path: &str, pemisah: Option<PemisahCsv>) -> Result<Vec<BarisSel>, String> {
+    let bytes = fs::read(path)
+        .map_err(|e| format!("Gagal membaca file: {}", e))?;
+    let teks = String::from_utf8_lossy(&bytes);
+    let teks = teks.trim_start_matches('\u{feff}'); // BOM dari Excel / export aplikasi ini
+
+    let pemisah = pemisah.unwrap_or_else(|| deteksi_pemisah(teks));
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(pemisah.byte())
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(teks.as_bytes());
+
+    let mut hasil = Vec::new();
+    for (idx, baris) in reader.records().enumerate() {
+        let baris = baris.map_err(|e| format!("Gagal membaca baris {}: {}", idx + 1, e))?;
+        let sel = baris.iter()
+            .map(|isi| if isi.trim().is_empty() { Sel::Kosong } else { Sel::Teks(isi.trim().to_string()) })
+            .collect();
+        hasil.push((idx + 1, sel));
+    }
+    Ok(hasil)
+}
+
+fn baca_excel(path: &str, lembar: Option<&str>) -> Result<(Vec<String>, Vec<BarisSel>), String> {
+    let mut workbook = open_workbook_auto(path)
+        .map_err(|e| format!("Gagal membuka file Excel: {}", e))?;
+
+    let daftar_lembar = workbook.sheet_names();
+    let nama = match lembar.filter(|l| !l.trim().is_empty()) {
+        Some(nama) if daftar_lembar.iter().any(|l| l == nama) => nama.to_string(),
+        Some(nama) => return Err(format!("Sheet '{}' tidak ada di file", nama)),
+        None => daftar_lembar.first().cloned().ok_or("File Excel tidak berisi sheet")?,
+    };
+
+    let range = workbook.worksheet_range(&nama)
+        .map_err(|e| format!("Gagal membaca sheet '{}': {}", nama, e))?;
+
+    // Nomor baris mengikuti posisi di sheet (sel kosong di atas tabel tidak ikut terbaca calamine)
+    let baris_awal = range.start().map_or(0, |(baris, _)| baris as usize);
+    let kolom_awal = range.start().map_or(0, |(_, kolom)| kolom as usize);
+    let hasil = range.rows()
+        .enumerate()
+        .map(|(idx, baris)| {
+            let sel = std::iter::repeat_n(Sel::Kosong, kolom_awal)
+                .chain(baris.iter().map(sel_excel))
+                .collect();
+            (baris_awal + idx + 1, sel)
+        })
+        .collect();
+
+    Ok((daftar_lembar, hasil))
+}
+
+fn sel_excel(data: &Data) -> Sel {
+    match data {
+        Data::Empty => Sel::Kosong,
+        Data::Int(n) => Sel::Angka(*n as f64),
+        Data::Float(n) => Sel::Angka(*n),
+        Data::DateTime(_) => data.as_datetime().map_or(Sel::Kosong, Sel::Tanggal),
+        Data::String(s) | Data::DateTimeIso(s) | Data::DurationIso(s) => Sel::Teks(s.trim().to_string()),
+        Data::Bool(b) => Sel::Teks(b.to_string()),
+        Data::Error(e) => Sel::Teks(format!("#{}", e)),
+    }
+}
+
+/// Ringkasan file untuk form pemetaan: sheet, judul kolom, contoh data & saran pemetaan
+pub fn ringkas(path: &str, tabel: &Tabel) -> FileImpor {
+    FileImpor {
+        path: path.to_string(),
+        lembar: tabel.lembar.clone(),
+        judul: tabel.judul.clone(),
+        contoh: tabel.baris.iter()
+            .take(JUMLAH_CONTOH)
+            .map(|(_, sel)| sel.iter().map(Sel::tampil).collect())
+            .collect(),
+        jumlah_baris: tabel.baris.len(),
+        saran: saran_pemetaan(&tabel.judul),
+    }
+}
+
+// ============================================
+// SARAN PEMETAAN
+// ============================================
+
+// Huruf & angka saja, huruf kecil; teks dalam [...] (satuan) dibuang
+fn normalisasi_judul(judul: &str) -> String {
+    let tanpa_satuan = match judul.find('[') {
+        Some(idx) => &judul[..idx],
+        None => judul,
+    };
+    tanpa_satuan.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Tebak field untuk setiap judul kolom (judul export aplikasi ini, nama field, label parameter, alias umum)
+pub fn saran_pemetaan(judul: &[String]) -> BTreeMap<String, String> {
+    let mut kandidat: HashMap<String, &str> = HashMap::new();
+    for field in semua_field() {
+        kandidat.insert(normalisasi_judul(&judul_kolom(field).unwrap_or_default()), field);
+        kandidat.insert(normalisasi_judul(field), field);
+        if let Some(label) = label_parameter(field) {
+            // "Besi (Fe)" juga dikenali sebagai "Besi"
+            let singkat = label.split(" (").next().unwrap_or(label);
+            kandidat.entry(normalisasi_judul(singkat)).or_insert(field);
+        }
+    }
+    for (alias, field) in ALIAS_JUDUL {
+        kandidat.entry(alias.to_string()).or_insert(field);
+    }
+
+    let mut saran = BTreeMap::new();
+    for judul in judul {
+        if let Some(field) = kandidat.get(&normalisasi_judul(judul)) {
+            saran.entry(field.to_string()).or_insert_with(|| judul.clone());
+        }
+    }
+    saran
+}
+
+// ============================================
+// VALIDASI & PEMBENTUKAN RECORD
+// ============================================
+
+// Angka dari teks sesuai pemisah desimal, beserta kualifier "<" / ">"
+fn angka_teks(teks: &str, desimal: DesimalCsv) -> Result<(f64, Option<Kualifier>), String> {
+    let bukan_angka = || format!("'{}' bukan angka", teks);
+    let teks = teks.trim();
+    let (kualifier, sisa) = match teks.chars().next() {
+        Some('<') => (Some(Kualifier::KurangDari), &teks[1..]),
+        Some('>') => (Some(Kualifier::LebihDari), &teks[1..]),
+        _ => (None, teks),
+    };
+    let sisa: String = sisa.chars().filter(|c| !c.is_whitespace()).collect();
+    let normal = match desimal {
+        // "1.234,5" -> "1234.5"; "0.5" tanpa koma dianggap sudah bertitik desimal
+        DesimalCsv::Koma if sisa.contains(',') => sisa.replace('.', "").replace(',', "."),
+        DesimalCsv::Koma => sisa,
+        // "1,234.5" -> "1234.5"; koma lain ("0,15") kemungkinan desimal koma, jangan dibuang diam-diam
+        DesimalCsv::Titik if sisa.contains(',') => {
+            if !pemisah_ribuan(&sisa) {
+                return Err(format!("'{}' memakai koma yang bukan pemisah ribuan (pilih desimal: koma)", teks));
+            }
+            sisa.replace(',', "")
+        }
+        DesimalCsv::Titik => sisa,
+    };
+    normal.parse::<f64>().ok().filter(|n| n.is_finite()).map(|n| (n, kualifier)).ok_or_else(bukan_angka)
+}
+
+// Koma sebagai pemisah ribuan: "1,234", "12,345,678.5" (grup pertama 1-3 digit, sisanya tepat 3 digit)
+fn pemisah_ribuan(teks: &str) -> bool {
+    let (bulat, pecahan) = teks.split_once('.').unwrap_or((teks, ""));
+    if pecahan.contains(',') {
+        return false;
+    }
+    let bulat = bulat.strip_prefix(['-', '+']).unwrap_or(bulat);
+    let mut grup = bulat.split(',');
+    let pertama = grup.next().unwrap_or_default();
+    let digit = |g: &str| g.bytes().all(|b| b.is_ascii_digit());
+    (1..=3).contains(&pertama.len()) && digit(pertama) && !pertama.starts_with('0')
+        && grup.all(|g| g.len() == 3 && digit(g))
+}
+
+fn nilai_angka(sel: &Sel, desimal: DesimalCsv) -> Result<Option<(f64, Option<Kualifier>)>, String> {
+    match sel {
+        Sel::Kosong => Ok(None),
+        Sel::Angka(n) => Ok(Some((*n, None))),
+        // "-" dan "ttd" lazim dipakai untuk parameter yang tidak diukur
+        Sel::Teks(t) if matches!(t.trim().to_lowercase().as_str(), "" | "-" | "--" | "ttd" | "tt" | "n/a" | "na") => Ok(None),
+        Sel::Teks(t) => angka_teks(t, desimal).map(Some),
+        Sel::Tanggal(_) => Err("berisi tanggal, bukan angka".to_string()),
+    }
+}
+
+fn nilai_tanggal(sel: &Sel) -> Result<Option<NaiveDate>, String> {
+    let tanggal = match sel {
+        Sel::Kosong => return Ok(None),
+        Sel::Tanggal(waktu) => waktu.date(),
+        Sel::Teks(t) => tanggal::parse_tanggal(t).ok_or_else(|| format!("'{}' bukan tanggal yang dikenali", t))?,
+        Sel::Angka(n) => return Err(format!("'{}' bukan tanggal (format sel bukan tanggal)", n)),
+    };
+    // Sel jam saja di Excel terbaca sebagai tanggal 1899-12-31
+    if tanggal.year() < 1900 {
+        return Err(format!("tanggal {} tidak valid", tanggal));
+    }
+    Ok(Some(tanggal))
+}
+
+fn nilai_waktu(sel: &Sel) -> Result<Option<NaiveTime>, String> {
+    match sel {
+        Sel::Kosong => Ok(None),
+        Sel::Tanggal(waktu) => Ok(Some(waktu.time())),
+        Sel::Teks(t) => tanggal::parse_waktu(t).map(Some).ok_or_else(|| format!("'{}' bukan jam (HH:MM)", t)),
+        // Jam Excel tanpa format: pecahan hari (0.5 = 12:00)
+        Sel::Angka(n) if (0.0..1.0).contains(n) => {
+            let detik = (n * 86_400.0).round() as u32;
+            Ok(NaiveTime::from_num_seconds_from_midnight_opt(detik, 0))
+        }
+        Sel::Angka(n) => Err(format!("'{}' bukan jam (HH:MM)", n)),
+    }
+}
+
+// Konversi satuan per kolom parameter, dibaca dari judul kolom (misal "Besi [µg/L]")
+fn satuan_kolom(field: &str, judul: &str) -> Option<String> {
+    let tokens: Vec<&str> = judul.split_whitespace().collect();
+    satuan::deteksi(field, &tokens).map(|s| s.trim_matches(|c| c == '[' || c == ']').to_string())
+}
+
+/// Validasi seluruh baris sesuai pemetaan. Error pemetaan (field / kolom tidak dikenal) menggagalkan semuanya;
+/// error nilai dicatat per baris.
+pub fn validasi(tabel: &Tabel, pemetaan: &PemetaanImpor) -> Result<HasilImporTabel, String> {
+    // 1. Periksa pemetaan
+    let mut kolom: Vec<(&str, usize)> = Vec::new();
+    for (field, judul) in &pemetaan.kolom {
+        if judul.trim().is_empty() { continue; }
+        if !field_dikenal(field) {
+            return Err(format!("Field tidak dikenal: {}", field));
+        }
+        let idx = tabel.judul.iter().position(|j| j == judul)
+            .ok_or_else(|| format!("Kolom '{}' tidak ada di file", judul))?;
+        kolom.push((field.as_str(), idx));
+    }
+    for wajib in FIELD_WAJIB {
+        if !kolom.iter().any(|(field, _)| *field == wajib) {
+            return Err(format!("Kolom untuk {} wajib dipetakan", judul_kolom(wajib).unwrap_or_default()));
+        }
+    }
+
+    // 2. Satuan tiap kolom parameter (dicatat sekali per kolom)
+    let mut peringatan = Vec::new();
+    let mut satuan: HashMap<&str, String> = HashMap::new();
+    for (field, idx) in &kolom {
+        if !NAMA_PARAMETER.contains(field) { continue; }
+        let judul = &tabel.judul[*idx];
+        let Some(s) = satuan_kolom(field, judul) else { continue };
+        match satuan::ke_satuan_baku(field, 1.0, Some(&s)) {
+            Konversi::Baku(_) => {}
+            Konversi::Dikonversi { faktor, .. } => peringatan.push(format!(
+                "Kolom '{}': satuan {} dikonversi ke {} (x{})", judul, s, satuan_parameter(field).unwrap_or("-"), faktor
+            )),
+            Konversi::TidakDikenal(_) => peringatan.push(format!("Kolom '{}': {}", judul, satuan::pesan_tidak_dikenal(field, &s))),
+        }
+        satuan.insert(field, s);
+    }
+
+    // 3. Setiap baris
+    let mut hasil: Vec<BarisImpor> = Vec::with_capacity(tabel.baris.len());
+    let mut kunci_baris: HashMap<String, usize> = HashMap::new();
+    for (nomor, sel) in &tabel.baris {
+        let mut record = KualitasAirRecord::default();
+        let mut error = Vec::new();
+        let mut catatan = Vec::new();
+        let mut field_gagal: HashSet<&str> = HashSet::new();
+
+        for (field, idx) in &kolom {
+            let isi = sel.get(*idx).unwrap_or(&Sel::Kosong);
+            if let Err(e) = isi_field(&mut record, field, isi, pemetaan.desimal, satuan.get(field).map(String::as_str)) {
+                error.push(format!("Kolom '{}': {}", tabel.judul[*idx], e));
+                field_gagal.insert(field);
+            }
+        }
+
+        // Field wajib yang formatnya salah sudah dicatat di atas
+        for wajib in FIELD_WAJIB {
+            let kosong = match wajib {
+                "tanggal_sampling" => record.tanggal_sampling.is_none(),
+                field => record.metadata(field).is_none(),
+            };
+            if kosong && !field_gagal.contains(wajib) {
+                error.push(format!("{} wajib diisi", judul_kolom(wajib).unwrap_or_default()));
+            }
+        }
+
+        if record.tahun.is_none() {
+            record.tahun = record.tanggal_sampling.map(|t| t.year());
+        }
+        if record.tersensor.iter().any(|t| t.kualifier == Kualifier::LebihDari) {
+            catatan.push("Terdapat nilai di atas batas ukur (>)".to_string());
+        }
+
+        // Baris ganda di dalam file yang sama
+        let mut duplikat = false;
+        if error.is_empty() {
+            let kunci = kunci_duplikat(&record);
+            if let Some(sebelumnya) = kunci_baris.get(&kunci) {
+                catatan.push(format!("Pos, tanggal & waktu sama dengan baris {}", sebelumnya));
+                duplikat = true;
+            } else {
+                kunci_baris.insert(kunci, *nomor);
+            }
+        }
+
+        hasil.push(BarisImpor {
+            baris: *nomor,
+            record: error.is_empty().then_some(record),
+            error,
+            peringatan: catatan,
+            duplikat,
+        });
+    }
+
+    let jumlah_valid = hasil.iter().filter(|b| b.record.is_some()).count();
+    Ok(HasilImporTabel {
+        jumlah_gagal: hasil.len() - jumlah_valid,
+        jumlah_valid,
+        baris: hasil,
+        peringatan,
+        ids: Vec::new(),
+    })
+}
+
+// Isi satu field record dari sel
+fn isi_field(
+    record: &mut KualitasAirRecord,
+    field: &str,
+    sel: &Sel,
+    desimal: DesimalCsv,
+    satuan_kolom: Option<&str>
+) -> Result<(), String> {
+    if NAMA_PARAMETER.contains(&field) {
+        let Some((nilai, kualifier)) = nilai_angka(sel, desimal)? else { return Ok(()) };
+        let nilai = match satuan::ke_satuan_baku(field, nilai, satuan_kolom) {
+            Konversi::Baku(n) | Konversi::Dikonversi { nilai: n, .. } | Konversi::TidakDikenal(n) => n,
+        };
+
+        if field == "ph" && !(0.0..=14.0).contains(&nilai) {
+            return Err(format!("pH {} di luar rentang 0-14", nilai));
+        }
+        if field != "temperatur" && nilai < 0.0 {
+            return Err(format!("nilai {} tidak boleh negatif", nilai));
+        }
+
+        if let Some(kualifier) = kualifier {
+            record.tersensor.push(NilaiTersensor { parameter: field.to_string(), kualifier, batas_deteksi: nilai });
+        }
+        if let Some(slot) = record.parameter_mut(field) {
+            *slot = Some(nilai);
+        }
+        return Ok(());
+    }
+
+    match field {
+        "tanggal_sampling" => record.tanggal_sampling = nilai_tanggal(sel)?,
+        "waktu_sampling" => record.waktu_sampling = nilai_waktu(sel)?,
+        "tahun" => {
+            record.tahun = match nilai_angka(sel, desimal)? {
+                Some((n, _)) if n.fract() == 0.0 && (1900.0..=2100.0).contains(&n) => Some(n as i32),
+                Some((n, _)) => return Err(format!("tahun {} tidak valid", n)),
+                None => None,
+            };
+        }
+        "kelas_baku_mutu" => {
+            if !sel.kosong() {
+                let teks = sel.tampil();
+                record.kelas_baku_mutu = Some(BakuMutuClass::dari_teks(&teks)
+                    .ok_or_else(|| format!("'{}' bukan kelas baku mutu (I-IV)", teks))?);
+            }
+        }
+        metadata => {
+            if let Some(slot) = record.metadata_mut(metadata) {
+                *slot = Some(sel.tampil()).filter(|t| !t.trim().is_empty());
+            }
+        }
+    }
+    Ok(())
+}
+
+// Pos + tanggal + waktu sebagai penanda data yang sama
+fn kunci_duplikat(record: &KualitasAirRecord) -> String {
+    format!(
+        "{}|{}|{}",
+        record.nama_pos.as_deref().unwrap_or_default().trim().to_lowercase(),
+        record.tanggal_sampling.map(|t| t.to_string()).unwrap_or_default(),
+        record.waktu_sampling.map(|w| w.format("%H:%M").to_string()).unwrap_or_default()
+    )
+}
+
+/// Tandai baris yang sudah ada di database (pos, tanggal & waktu sampling sama)
+pub async fn tandai_duplikat(pool: &SqlitePool, hasil: &mut HasilImporTabel) -> Result<(), String> {
+    let rows: Vec<(Option<String>, Option<NaiveDate>, Option<NaiveTime>)> = sqlx::query_as(
+        "SELECT nama_pos, tanggal_sampling, waktu_sampling FROM v_kualitas_air WHERE tanggal_sampling IS NOT NULL"
+    )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Gagal memeriksa data ganda: {}", e))?;
+
+    let tersimpan: HashSet<String> = rows.into_iter()
+        .map(|(nama_pos, tanggal_sampling, waktu_sampling)| kunci_duplikat(&KualitasAirRecord {
+            nama_pos, tanggal_sampling, waktu_sampling, ..Default::default()
+        }))
+        .collect();
+
+    for baris in hasil.baris.iter_mut() {
+        let Some(record) = &baris.record else { continue };
+        if tersimpan.contains(&kunci_duplikat(record)) {
+            baris.peringatan.push("Data pos, tanggal & waktu ini sudah ada di database".to_string());
+            baris.duplikat = true;
+        }
+    }
+    Ok(())
+}
+
+// ============================================
+// PEMETAAN TERSIMPAN
+// ============================================
+
+pub fn pemetaan_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_dir = app.path().app_local_data_dir()
+        .map_err(|e| format!("Gagal mendapatkan direktori app: {}", e))?;
+    Ok(app_dir.join(PEMETAAN_DIRNAME))
+}
+
+// Nama file dari nama pemetaan: huruf/angka saja, spasi jadi garis bawah
+fn nama_file(nama: &str) -> String {
+    let slug: String = nama.trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    format!("{}.json", slug)
+}
+
+// Nama berbeda bisa menghasilkan file yang sama ("Lab A" dan "lab-a" -> lab_a.json).
+// File yang sudah ada hanya boleh ditimpa/dihapus oleh pemetaan dengan nama yang sama.
+fn cek_pemilik_file(path: &Path, nama: &str) -> Result<(), String> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let pemilik = fs::read_to_string(path).ok()
+        .and_then(|isi| serde_json::from_str::<PemetaanImpor>(&isi).ok())
+        .map(|pemetaan| pemetaan.nama);
+    match pemilik {
+        Some(pemilik) if pemilik.trim() == nama.trim() => Ok(()),
+        Some(pemilik) => Err(format!(
+            "Nama '{}' bentrok dengan pemetaan tersimpan '{}', gunakan nama lain", nama.trim(), pemilik
+        )),
+        None => Err(format!(
+            "File {} sudah ada dan bukan pemetaan yang valid, gunakan nama lain",
+            path.file_name().unwrap_or_default().to_string_lossy()
+        )),
+    }
+}
+
+/// Seluruh pemetaan tersimpan, terurut nama file.
+/// File yang tidak valid dilewati dan dilaporkan di peringatan.
+pub fn load_all_pemetaan(app: &AppHandle) -> Result<DaftarPemetaan, String> {
+    let mut daftar = DaftarPemetaan { pemetaan: Vec::new(), peringatan: Vec::new() };
+    let dir = pemetaan_dir(app)?;
+    if !dir.exists() {
+        return Ok(daftar);
+    }
+
+    let entries = fs::read_dir(&dir)
+        .map_err(|e| format!("Gagal membaca folder pemetaan: {}", e))?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let hasil = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|isi| serde_json::from_str::<PemetaanImpor>(&isi).map_err(|e| e.to_string()));
+        match hasil {
+            Ok(pemetaan) => daftar.pemetaan.push(pemetaan),
+            Err(e) => daftar.peringatan.push(format!(
+                "Pemetaan {} dilewati: {}", path.file_name().unwrap_or_default().to_string_lossy(), e
+            )),
+        }
+    }
+    Ok(daftar)
+}
+
+/// Simpan pemetaan (menimpa pemetaan tersimpan dengan nama yang sama)
+pub fn save_pemetaan(app: &AppHandle, pemetaan: &PemetaanImpor) -> Result<PathBuf, String> {
+    if pemetaan.nama.trim().is_empty() {
+        return Err("Nama pemetaan wajib diisi".to_string());
+    }
+    if let Some(field) = pemetaan.kolom.keys().find(|f| !field_dikenal(f)) {
+        return Err(format!("Field tidak dikenal: {}", field));
+    }
+
+    let dir = pemetaan_dir(app)?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Gagal membuat folder pemetaan: {}", e))?;
+
+    let isi = serde_json::to_string_pretty(pemetaan)
+        .map_err(|e| format!("Gagal menyusun pemetaan: {}", e))?;
+    let path = dir.join(nama_file(&pemetaan.nama));
+    cek_pemilik_file(&path, &pemetaan.nama)?;
+    fs::write(&path, isi)
+        .map_err(|e| format!("Gagal menyimpan pemetaan: {}", e))?;
+
+    Ok(path)
+}
+
+/// Hapus pemetaan tersimpan
+pub fn delete_pemetaan(app: &AppHandle, nama: &str) -> Result<(), String> {
+    let path = pemetaan_dir(app)?.join(nama_file(nama));
+    if !path.exists() {
+        return Err(format!("Pemetaan '{}' tidak ditemukan", nama));
+    }
+    cek_pemilik_file(&path, nama)?;
+    fs::remove_file(&path)
+        .map_err(|e| format!("Gagal menghapus pemetaan: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nama_file_bentrok_tidak_saling_menimpa() {
+        let dir = std::env::temp_dir().join(format!("pemetaan_impor_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let pemetaan = |nama: &str| PemetaanImpor {
+            nama: nama.to_string(),
+            lembar: None,
+            baris_header: 1,
+            pemisah: None,
+            desimal: DesimalCsv::Titik,
+            kolom: BTreeMap::new(),
+        };
+        let path = dir.join(nama_file("Lab A"));
+        assert_eq!(path, dir.join(nama_file("lab-a")));
+        fs::write(&path, serde_json::to_string(&pemetaan("Lab A")).unwrap()).unwrap();
+
+        assert!(cek_pemilik_file(&path, "Lab A").is_ok(), "simpan ulang dengan nama sama boleh");
+        assert!(cek_pemilik_file(&path, "lab-a").unwrap_err().contains("'Lab A'"));
+        assert!(cek_pemilik_file(&dir.join(nama_file("Lab B")), "Lab B").is_ok());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
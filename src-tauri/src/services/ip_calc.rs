@@ -1,4 +1,9 @@
+use crate::models::baku_mutu::{BatasParameter, LimitType};
+use crate::models::hasil_perhitungan::{HasilIp, RincianParameterIp};
 use crate::models::kualitas_air::KualitasAirRecord;
+use crate::models::nilai_tersensor::KebijakanSensor;
+use crate::models::suhu_alamiah::SuhuAlamiah;
+use crate::services::suhu_alamiah;
 
 // Enum untuk membedakan jenis parameter
 enum ParameterType {
@@ -6,143 +11,129 @@ enum ParameterType {
     Inverse, // Semakin besar semakin baik (DO / Oksigen)
 }
 
-/// Fungsi Utama Menghitung Indeks Pencemaran (IP)
-/// Sesuai Kepmen LH No. 115 Tahun 2003
-/// Revisi: Logaritma Perataan & Temperatur Deviasi 3
-pub fn calculate_ip(data: &KualitasAirRecord) -> (f64, String) {
-    let mut ratios: Vec<f64> = Vec::new();
+// =====================================================================
+// HELPER: Transformasi Logaritma
+// =====================================================================
+// Jika C/L > 1, gunakan rumus: 1 + 5 * log10(C/L)
+// Jika C/L <= 1, gunakan nilai asli.
+fn transform_ratio(raw_ratio: f64) -> f64 {
+    if raw_ratio <= 1.0 {
+        // Memenuhi baku mutu, pakai nilai asli
+        raw_ratio
+    } else {
+        // Melampaui baku mutu, pakai logaritma (Smoothing)
+        // Rumus: 1 + 5 * log10(R)
+        1.0 + (5.0 * raw_ratio.log10())
+    }
+}
 
-    // =====================================================================
-    // HELPER: Transformasi Logaritma
-    // =====================================================================
-    // Jika C/L > 1, gunakan rumus: 1 + 5 * log10(C/L)
-    // Jika C/L <= 1, gunakan nilai asli.
-    let mut add_ratio = |val: Option<f64>, baku: f64, jenis: ParameterType| {
-        if let Some(c) = val {
-            // 1. Hitung Rasio Awal (Raw Ratio)
-            let raw_ratio = match jenis {
-                ParameterType::Direct => c / baku,      // Kasus Normal (C/L)
-                ParameterType::Inverse => baku / c,     // Kasus Terbalik (L/C) -> Misal DO
-            };
-
-            // 2. Evaluasi Nilai Rentang (Transformasi Logaritma)
-            let final_ratio = if raw_ratio <= 1.0 {
-                // Memenuhi baku mutu, pakai nilai asli
-                raw_ratio 
-            } else {
-                // Melampaui baku mutu, pakai logaritma (Smoothing)
-                // Rumus: 1 + 5 * log10(R)
-                1.0 + (5.0 * raw_ratio.log10())
-            };
+// Rasio terburuk untuk parameter terbalik dengan konsentrasi <= 0 (misal DO tersensor dengan
+// kebijakan Nol): L/C tidak terdefinisi, jadi dipakai rasio yang setelah transformasi
+// (1 + 5 * log10(100) = 11) sudah masuk Cemar Berat, bukan inf atau parameter dilewati.
+const RASIO_TERBURUK: f64 = 100.0;
 
-            ratios.push(final_ratio);
-        }
-    };
+// Hitung Rasio Awal (Raw Ratio)
+fn ratio(c: f64, baku: f64, jenis: ParameterType) -> f64 {
+    match jenis {
+        ParameterType::Direct => c / baku,      // Kasus Normal (C/L)
+        ParameterType::Inverse if c <= 0.0 => RASIO_TERBURUK,
+        ParameterType::Inverse => baku / c,     // Kasus Terbalik (L/C) -> Misal DO
+    }
+}
 
-    // =====================================================================
-    // GRUP 1: PARAMETER FISIKA & KIMIA (DIRECT / PENCEMAR BIASA)
-    // =====================================================================
-    // Logic: Semakin tinggi nilai, semakin buruk (C/L)
-    
-    // -- Fisika --
-    add_ratio(data.tds, 1000.0, ParameterType::Direct);
-    add_ratio(data.tss, 50.0, ParameterType::Direct);
-    add_ratio(data.warna, 50.0, ParameterType::Direct);
-
-    // -- Nutrient --
-    add_ratio(data.amoniak, 0.2, ParameterType::Direct);
-    add_ratio(data.nitrat, 10.0, ParameterType::Direct);
-    add_ratio(data.nitrit, 0.06, ParameterType::Direct);
-    add_ratio(data.fosfat, 0.2, ParameterType::Direct);
-    add_ratio(data.deterjen, 0.2, ParameterType::Direct);
-
-    // -- Logam Berat --
-    add_ratio(data.arsen, 0.05, ParameterType::Direct);
-    add_ratio(data.mangan, 0.4, ParameterType::Direct);
-    add_ratio(data.tembaga, 0.02, ParameterType::Direct);
-    add_ratio(data.merkuri, 0.002, ParameterType::Direct);
-    
-    // -- Anorganik Lain --
-    add_ratio(data.sianida, 0.02, ParameterType::Direct);
-    add_ratio(data.fluorida, 1.5, ParameterType::Direct);
-    add_ratio(data.klorida, 300.0, ParameterType::Direct); 
-    add_ratio(data.belerang, 0.002, ParameterType::Direct);
-
-    // -- Organik --
-    add_ratio(data.cod, 25.0, ParameterType::Direct);
-    add_ratio(data.bod, 3.0, ParameterType::Direct);
-    add_ratio(data.minyak_dan_lemak, 1.0, ParameterType::Direct);
-    add_ratio(data.fenol, 0.005, ParameterType::Direct);
-
-    // -- Mikrobiologi --
-    add_ratio(data.total_coliform, 1000.0, ParameterType::Direct);
+// Rasio mentah satu parameter sesuai jenis batasnya (None jika batas tidak lengkap)
+fn raw_ratio(c: f64, batas: &BatasParameter, suhu_alamiah: f64) -> Option<f64> {
+    match batas.jenis {
+        // Parameter pencemar biasa: semakin tinggi nilai, semakin buruk (C/L)
+        LimitType::Max => batas.batas_atas.map(|baku| ratio(c, baku, ParameterType::Direct)),
 
+        // Parameter terbalik (DO): semakin tinggi nilai, semakin BAIK (L/C)
+        LimitType::Min => batas.batas_bawah.map(|baku| ratio(c, baku, ParameterType::Inverse)),
 
-    // =====================================================================
-    // GRUP 2: PARAMETER TERBALIK (INVERSE) - OKSIGEN TERLARUT (DO)
-    // =====================================================================
-    // Logic: Semakin tinggi nilai, semakin BAIK (L/C). 
-    add_ratio(data.oksigen, 4.0, ParameterType::Inverse);
+        // TEMPERATUR (Deviasi 3 dari suhu alamiah)
+        // Hitung selisih mutlak (Deviasi), semakin besar semakin buruk
+        LimitType::Deviasi => batas.batas_atas.map(|baku_deviasi| {
+            let deviasi = (c - suhu_alamiah).abs();
+            ratio(deviasi, baku_deviasi, ParameterType::Direct)
+        }),
+
+        // pH (Rentang, misal 6 - 9)
+        // Logic: Hitung jarak dari rentang terdekat.
+        LimitType::Range => match (batas.batas_bawah, batas.batas_atas) {
+            (Some(bawah), Some(atas)) => Some(if c < bawah {
+                // Terlalu Asam: Baku Min / Nilai -> 6.0 / 5.0 = 1.2 (nilai <= 0 -> RASIO_TERBURUK)
+                ratio(c, bawah, ParameterType::Inverse)
+            } else if c > atas {
+                // Terlalu Basa: Nilai / Baku Max -> 10.0 / 9.0 = 1.11
+                c / atas
+            } else {
+                // Normal (Memenuhi)
+                // Dalam logaritma smoothing, nilai memenuhi baku mutu (<=1) tidak di-log
+                1.0
+            }),
+            _ => None,
+        },
+    }
+}
 
+/// Fungsi Utama Menghitung Indeks Pencemaran (IP)
+/// Sesuai Kepmen LH No. 115 Tahun 2003
+/// Revisi: Logaritma Perataan & Temperatur Deviasi 3
+/// Baku mutu diambil dari tabel terpilih (kelas PP 22/2021 atau set kustom)
+/// Hasil memuat rincian per parameter agar parameter penyebab pencemaran terlihat.
+/// Deviasi temperatur dihitung terhadap suhu alamiah pos (per bulan jika tersedia).
+/// Nilai tersensor (< / > batas deteksi) diganti sesuai kebijakan substitusi.
+pub fn calculate_ip(
+    data: &KualitasAirRecord,
+    baku_mutu: &[BatasParameter],
+    tabel_suhu: &[SuhuAlamiah],
+    kebijakan: KebijakanSensor
+) -> HasilIp {
+    let mut rincian: Vec<RincianParameterIp> = Vec::new();
+    let suhu_alamiah = suhu_alamiah::suhu_untuk(tabel_suhu, data);
 
     // =====================================================================
-    // GRUP 3: PARAMETER KHUSUS (TEMPERATURE & pH)
+    // EVALUASI PER PARAMETER SESUAI TABEL BAKU MUTU
     // =====================================================================
-    
-    // 1. TEMPERATUR (Deviasi 3)
-    // Baku Mutu: Deviasi 3 derajat dari suhu alamiah.
-    // Karena belum ada input suhu alamiah, kita asumsi rata-rata tropis = 27.0 C
-    if let Some(val) = data.temperatur {
-        let suhu_alamiah_default = 27.0; 
-        
-        // Hitung selisih mutlak (Deviasi)
-        let deviasi = (val - suhu_alamiah_default).abs();
-        
-        // Baku mutu deviasi adalah 3
-        let baku_deviasi = 3.0;
-
-        // Masukkan sebagai parameter Direct (Semakin besar deviasi, semakin buruk)
-        add_ratio(Some(deviasi), baku_deviasi, ParameterType::Direct);
-    }
+    for batas in baku_mutu {
+        let Some(c) = data.nilai_hitung(&batas.parameter, kebijakan) else { continue };
+        let Some(rasio) = raw_ratio(c, batas, suhu_alamiah) else { continue };
 
-    // 2. pH (Rentang 6 - 9)
-    // Logic: Hitung jarak dari rentang terdekat.
-    if let Some(val) = data.ph {
-        let raw_ratio = if val < 6.0 {
-            // Terlalu Asam: Baku Min / Nilai -> 6.0 / 5.0 = 1.2
-            6.0 / val
-        } else if val > 9.0 {
-            // Terlalu Basa: Nilai / Baku Max -> 10.0 / 9.0 = 1.11
-            val / 9.0
-        } else {
-            // Normal (Memenuhi)
-            // Dalam logaritma smoothing, nilai memenuhi baku mutu (<=1) tidak di-log
-            // Kita beri nilai kecil (misal 0.1) atau maksimal 1.0
-            1.0 
-        };
-        
-        // Terapkan Logaritma jika > 1.0
-        if raw_ratio > 1.0 {
-            ratios.push(1.0 + (5.0 * raw_ratio.log10()));
-        } else {
-            ratios.push(raw_ratio);
-        }
+        rincian.push(RincianParameterIp {
+            parameter: batas.parameter.clone(),
+            jenis: batas.jenis,
+            konsentrasi: c,
+            tersensor: data.tersensor.iter().any(|t| t.parameter == batas.parameter),
+            batas_bawah: batas.batas_bawah,
+            batas_atas: batas.batas_atas,
+            rasio,
+            rasio_transformasi: transform_ratio(rasio),
+            melampaui: rasio > 1.0,
+        });
     }
 
     // =====================================================================
     // FINAL CALCULATION (MAX & AVG)
     // =====================================================================
-    
-    if ratios.is_empty() {
-        return (0.0, "Data Kosong".to_string());
+
+    if rincian.is_empty() {
+        return HasilIp {
+            nilai_ip: 0.0,
+            status: "Data Kosong".to_string(),
+            rasio_maks: 0.0,
+            rasio_rata: 0.0,
+            suhu_alamiah,
+            kebijakan_sensor: kebijakan,
+            parameter: rincian,
+        };
     }
 
     // Cari Max & Avg dari rasio yang SUDAH DITRANSFORMASI
     // fold dipakai karena f64 tidak bisa pakai .max() langsung
-    let max_r = ratios.iter().fold(0.0/0.0, |m, v| v.max(m)); 
-    
-    let sum_r: f64 = ratios.iter().sum();
-    let avg_r = sum_r / ratios.len() as f64;
+    let max_r = rincian.iter().fold(f64::NEG_INFINITY, |m, r| r.rasio_transformasi.max(m));
+
+    let sum_r: f64 = rincian.iter().map(|r| r.rasio_transformasi).sum();
+    let avg_r = sum_r / rincian.len() as f64;
 
     // Rumus Akar IP (Nemerow)
     let pi_score = ((max_r.powi(2) + avg_r.powi(2)) / 2.0).sqrt();
@@ -159,6 +150,136 @@ pub fn calculate_ip(data: &KualitasAirRecord) -> (f64, String) {
     };
 
     // Return 2 desimal
-    let final_score = (pi_score * 100.0).round() / 100.0;
-    (final_score, status)
-}
\ No newline at end of file
+    HasilIp {
+        nilai_ip: (pi_score * 100.0).round() / 100.0,
+        status,
+        rasio_maks: max_r,
+        rasio_rata: avg_r,
+        suhu_alamiah,
+        kebijakan_sensor: kebijakan,
+        parameter: rincian,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::baku_mutu::BakuMutuClass;
+    use crate::services::baku_mutu::standar_kelas;
+    use chrono::NaiveDate;
+
+    fn rincian<'a>(hasil: &'a HasilIp, parameter: &str) -> &'a RincianParameterIp {
+        hasil.parameter.iter().find(|r| r.parameter == parameter).expect("parameter tidak dihitung")
+    }
+
+    fn hanya(parameter: &str, jenis: LimitType, bawah: Option<f64>, atas: Option<f64>) -> Vec<BatasParameter> {
+        vec![BatasParameter {
+            parameter: parameter.to_string(),
+            jenis,
+            batas_bawah: bawah,
+            batas_atas: atas,
+            satuan: None,
+            rujukan: None,
+        }]
+    }
+
+    #[test]
+    fn ip_mengikuti_kelas_baku_mutu() {
+        let data = KualitasAirRecord { bod: Some(5.0), ..Default::default() };
+
+        // BOD 5 mg/L: melampaui Kelas II (3 mg/L), memenuhi Kelas III (6 mg/L)
+        let kelas_ii = calculate_ip(&data, &standar_kelas(BakuMutuClass::KelasII), &[], KebijakanSensor::Lod);
+        let kelas_iii = calculate_ip(&data, &standar_kelas(BakuMutuClass::KelasIII), &[], KebijakanSensor::Lod);
+        assert!(rincian(&kelas_ii, "bod").melampaui);
+        assert!(!rincian(&kelas_iii, "bod").melampaui);
+        assert_eq!(kelas_ii.status, "Cemar Ringan");
+        assert_eq!(kelas_iii.status, "Memenuhi Baku Mutu");
+    }
+
+    #[test]
+    fn ip_set_kustom_hanya_menghitung_parameternya() {
+        let data = KualitasAirRecord { bod: Some(5.0), tss: Some(500.0), ..Default::default() };
+
+        let set = hanya("bod", LimitType::Max, None, Some(2.5));
+        let hasil = calculate_ip(&data, &set, &[], KebijakanSensor::Lod);
+        assert_eq!(hasil.parameter.len(), 1);
+        assert_eq!(rincian(&hasil, "bod").rasio, 2.0);
+    }
+
+    #[test]
+    fn ip_ph_rasio_terhadap_batas_rentang_terdekat() {
+        let baku_mutu = hanya("ph", LimitType::Range, Some(6.0), Some(9.0));
+        let rasio_ph = |ph: f64| {
+            let data = KualitasAirRecord { ph: Some(ph), ..Default::default() };
+            rincian(&calculate_ip(&data, &baku_mutu, &[], KebijakanSensor::Lod), "ph").rasio
+        };
+
+        assert!((rasio_ph(5.0) - 1.2).abs() < 1e-9);        // Terlalu asam: 6 / 5
+        assert!((rasio_ph(10.0) - 10.0 / 9.0).abs() < 1e-9); // Terlalu basa: 10 / 9
+        assert_eq!(rasio_ph(7.5), 1.0);                        // Di dalam rentang
+        assert_eq!(rasio_ph(0.0), RASIO_TERBURUK);
+    }
+
+    #[test]
+    fn ip_temperatur_deviasi_terhadap_suhu_alamiah_pos() {
+        let baku_mutu = hanya("temperatur", LimitType::Deviasi, None, Some(3.0));
+        let tabel_suhu = [
+            SuhuAlamiah { id: Some(1), pos_id: 7, bulan: 0, suhu: 26.0 },
+            SuhuAlamiah { id: Some(2), pos_id: 7, bulan: 3, suhu: 29.0 },
+        ];
+        let sampel = |bulan: u32| KualitasAirRecord {
+            pos_id: Some(7),
+            tanggal_sampling: NaiveDate::from_ymd_opt(2024, bulan, 15),
+            temperatur: Some(31.0),
+            ..Default::default()
+        };
+
+        // Maret: suhu alamiah bulanan 29 °C -> deviasi 2 (memenuhi)
+        let maret = calculate_ip(&sampel(3), &baku_mutu, &tabel_suhu, KebijakanSensor::Lod);
+        assert_eq!(maret.suhu_alamiah, 29.0);
+        assert!(!rincian(&maret, "temperatur").melampaui);
+
+        // Juni: tidak ada nilai bulanan, pakai suhu tahunan 26 °C -> deviasi 5 (melampaui)
+        let juni = calculate_ip(&sampel(6), &baku_mutu, &tabel_suhu, KebijakanSensor::Lod);
+        assert_eq!(juni.suhu_alamiah, 26.0);
+        assert!((rincian(&juni, "temperatur").rasio - 5.0 / 3.0).abs() < 1e-9);
+
+        // Tabel milik pos lain diabaikan: suhu alamiah default
+        let pos_lain = KualitasAirRecord { pos_id: Some(8), ..sampel(3) };
+        let hasil = calculate_ip(&pos_lain, &baku_mutu, &tabel_suhu, KebijakanSensor::Lod);
+        assert_eq!(hasil.suhu_alamiah, suhu_alamiah::SUHU_ALAMIAH_DEFAULT);
+    }
+
+    #[test]
+    fn ip_do_tersensor_selalu_terhingga() {
+        use crate::models::nilai_tersensor::{Kualifier, NilaiTersensor};
+
+        let baku_mutu = hanya("oksigen", LimitType::Min, Some(4.0), None);
+        let data = KualitasAirRecord {
+            oksigen: Some(0.5),
+            tersensor: vec![NilaiTersensor {
+                parameter: "oksigen".to_string(),
+                kualifier: Kualifier::KurangDari,
+                batas_deteksi: 0.5,
+            }],
+            ..Default::default()
+        };
+
+        let hasil = |kebijakan| calculate_ip(&data, &baku_mutu, &[], kebijakan);
+        for (kebijakan, rasio) in [
+            (KebijakanSensor::Lod, 8.0),              // 4 / 0.5
+            (KebijakanSensor::SetengahLod, 16.0),     // 4 / 0.25
+            (KebijakanSensor::Nol, RASIO_TERBURUK),  // 4 / 0 tidak terdefinisi
+        ] {
+            let hasil = hasil(kebijakan);
+            assert_eq!(rincian(&hasil, "oksigen").rasio, rasio, "{:?}", kebijakan);
+            assert!(hasil.nilai_ip.is_finite(), "{:?}", kebijakan);
+        }
+        assert_eq!(hasil(KebijakanSensor::Nol).status, "Cemar Berat");
+
+        // Abaikan: DO tidak ikut dihitung
+        let abaikan = hasil(KebijakanSensor::Abaikan);
+        assert!(abaikan.parameter.is_empty());
+        assert_eq!(abaikan.status, "Data Kosong");
+    }
+}
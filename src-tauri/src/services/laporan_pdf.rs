@@ -0,0 +1,571 @@
+use crate::models::baku_mutu::{BatasParameter, LimitType};
+use crate::models::hasil_perhitungan::HasilIp;
+use crate::models::kualitas_air::{label_parameter, satuan_parameter, KualitasAirRecord};
+use crate::services::tanggal;
+use chrono::NaiveDate;
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Document, Object, Stream, StringFormat};
+
+// Ukuran A4 & batas area cetak (point)
+const LEBAR_HALAMAN: f32 = 595.28;
+const TINGGI_HALAMAN: f32 = 841.89;
+const KIRI: f32 = 45.0;
+const KANAN: f32 = 550.0;
+const ATAS: f32 = 800.0;
+const BAWAH: f32 = 60.0;
+
+// Logo PU diperkecil (300 px) dari src/assets/Logo_PU_(RGB).jpg agar ukuran PDF tetap kecil
+const LOGO: &[u8] = include_bytes!("../../assets/logo_pu_laporan.jpg");
+
+// Kop surat, sama dengan lembar cetak (cetak-kualitas-air.html)
+const KOP: [(&str, Huruf, f32); 3] = [
+    ("KEMENTERIAN PEKERJAAN UMUM", Huruf::Tebal, 14.0),
+    ("DIREKTORAT JENDERAL SUMBER DAYA AIR", Huruf::Tebal, 11.0),
+    ("Balai Wilayah Sungai Sulawesi IV Kendari", Huruf::Biasa, 10.0),
+];
+
+// Kelompok & urutan parameter, sama dengan lembar cetak
+const KELOMPOK_PARAMETER: &[(&str, &[&str])] = &[
+    ("I. PARAMETER FISIKA & UMUM", &["temperatur", "tds", "tss", "warna", "konduktivitas", "kekeruhan", "ph", "oksigen"]),
+    ("II. PARAMETER KIMIA (ANORGANIK & NUTRIEN)", &[
+        "klorida", "amoniak", "nitrat", "nitrit", "fosfat", "deterjen", "sianida", "fluorida", "belerang",
+    ]),
+    ("III. LOGAM BERAT", &["arsen", "besi", "mangan", "tembaga", "merkuri"]),
+    ("IV. PARAMETER ORGANIK", &["bod", "cod", "minyak_dan_lemak", "fenol"]),
+    ("V. MIKROBIOLOGI", &["total_coliform"]),
+    ("VI. LAIN-LAIN", &["debit"]),
+];
+
+// Kolom tabel parameter: (judul, lebar)
+const KOLOM_TABEL: [(&str, f32); 6] = [
+    ("No", 25.0), ("Parameter", 175.0), ("Satuan", 65.0), ("Hasil Uji", 75.0), ("Baku Mutu", 75.0), ("Keterangan", 90.0),
+];
+
+const MERAH: [f32; 3] = [0.75, 0.0, 0.0];
+const HITAM: [f32; 3] = [0.0, 0.0, 0.0];
+const ABU_JUDUL: [f32; 3] = [0.85, 0.88, 0.95];
+const ABU_KELOMPOK: [f32; 3] = [0.94, 0.94, 0.94];
+
+// Lebar glyph Helvetica & Helvetica-Bold (AFM Adobe, per 1000 unit) untuk ASCII 32..126
+const LEBAR_HELVETICA: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667, 611, 778,
+    722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278,
+    278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+const LEBAR_HELVETICA_BOLD: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, 975, 722, 722, 722, 722, 667, 611, 778,
+    722, 278, 556, 722, 611, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333,
+    278, 333, 584, 556, 333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611,
+    611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+/// Bahan laporan hasil analisa satu sampel
+pub struct DataLaporan<'a> {
+    pub record: &'a KualitasAirRecord,
+    pub baku_mutu: &'a [BatasParameter],
+    pub nama_baku_mutu: String, // Misal "Kelas II (PP No. 22 Tahun 2021)" atau nama set kustom
+    pub hasil_ip: &'a HasilIp,  // Nilai & status IP tersimpan, rincian per parameter untuk tanda melampaui
+    pub tanggal_cetak: NaiveDate,
+}
+
+#[derive(Clone, Copy)]
+enum Huruf {
+    Biasa, // Helvetica
+    Tebal, // Helvetica-Bold
+}
+
+impl Huruf {
+    fn nama(self) -> &'static str {
+        match self {
+            Huruf::Biasa => "F1",
+            Huruf::Tebal => "F2",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Rata {
+    Kiri,
+    Tengah,
+    Kanan,
+}
+
+// Teks ke WinAnsiEncoding (encoding font standar PDF); karakter di luar encoding menjadi "?"
+fn win_ansi(teks: &str) -> Vec<u8> {
+    teks.chars()
+        .map(|c| match c {
+            ' '..='~' | '\u{A0}'..='\u{FF}' => c as u32 as u8,
+            '–' => 0x96,
+            '—' => 0x97,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '•' => 0x95,
+            '…' => 0x85,
+            _ => b'?',
+        })
+        .collect()
+}
+
+// Lebar teks (point); karakter non-ASCII dianggap selebar angka kecuali "°"
+fn lebar_teks(teks: &str, huruf: Huruf, ukuran: f32) -> f32 {
+    let tabel = match huruf {
+        Huruf::Biasa => &LEBAR_HELVETICA,
+        Huruf::Tebal => &LEBAR_HELVETICA_BOLD,
+    };
+    let total: u32 = win_ansi(teks).iter()
+        .map(|&b| match b {
+            32..=126 => tabel[(b - 32) as usize] as u32,
+            0xB0 => 400,
+            _ => 556,
+        })
+        .sum();
+    total as f32 * ukuran / 1000.0
+}
+
+// Potong teks dengan "..." agar muat di lebar tertentu
+fn potong(teks: &str, huruf: Huruf, ukuran: f32, lebar: f32) -> String {
+    if lebar_teks(teks, huruf, ukuran) <= lebar {
+        return teks.to_string();
+    }
+    let mut hasil: String = teks.to_string();
+    while !hasil.is_empty() && lebar_teks(&format!("{}...", hasil), huruf, ukuran) > lebar {
+        hasil.pop();
+    }
+    format!("{}...", hasil.trim_end())
+}
+
+// Lebar & jumlah komponen warna gambar JPEG (dari marker SOF)
+fn info_jpeg(data: &[u8]) -> Option<(u16, u16, u8)> {
+    let mut i = 2;
+    while i + 9 < data.len() {
+        if data[i] != 0xFF {
+            return None;
+        }
+        let marker = data[i + 1];
+        let panjang = u16::from_be_bytes([data[i + 2], data[i + 3]]) as usize;
+        if matches!(marker, 0xC0..=0xC2) {
+            let tinggi = u16::from_be_bytes([data[i + 5], data[i + 6]]);
+            let lebar = u16::from_be_bytes([data[i + 7], data[i + 8]]);
+            return Some((lebar, tinggi, data[i + 9]));
+        }
+        i += 2 + panjang;
+    }
+    None
+}
+
+// Angka tanpa nol berlebih: 0.002, 7.2, 1000
+fn angka(nilai: f64) -> String {
+    let teks = format!("{:.4}", nilai);
+    teks.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn teks_baku_mutu(batas: Option<&BatasParameter>) -> String {
+    let Some(batas) = batas else { return "-".to_string() };
+    match (batas.jenis, batas.batas_bawah, batas.batas_atas) {
+        (LimitType::Max, _, Some(atas)) => angka(atas),
+        (LimitType::Min, Some(bawah), _) => format!("min. {}", angka(bawah)),
+        (LimitType::Range, Some(bawah), Some(atas)) => format!("{} - {}", angka(bawah), angka(atas)),
+        (LimitType::Deviasi, _, Some(atas)) => format!("Dev {}", angka(atas)),
+        _ => "-".to_string(),
+    }
+}
+
+// Kumpulan operasi gambar per halaman; posisi y berjalan dari atas ke bawah
+struct Kanvas {
+    halaman: Vec<Vec<Operation>>,
+    y: f32,
+}
+
+impl Kanvas {
+    fn baru() -> Kanvas {
+        Kanvas { halaman: vec![Vec::new()], y: ATAS }
+    }
+
+    fn tambah(&mut self, operator: &str, operand: Vec<Object>) {
+        if let Some(ops) = self.halaman.last_mut() {
+            ops.push(Operation::new(operator, operand));
+        }
+    }
+
+    // Pindah ke halaman baru jika sisa ruang kurang dari `tinggi` (true jika pindah)
+    fn pastikan_ruang(&mut self, tinggi: f32) -> bool {
+        if self.y - tinggi >= BAWAH {
+            return false;
+        }
+        self.halaman.push(Vec::new());
+        self.y = ATAS;
+        true
+    }
+
+    fn warna(&mut self, operator: &str, [r, g, b]: [f32; 3]) {
+        self.tambah(operator, vec![r.into(), g.into(), b.into()]);
+    }
+
+    // Teks pada garis dasar y; x adalah tepi kiri, tengah, atau kanan sesuai perataan
+    fn teks(&mut self, x: f32, y: f32, teks: &str, huruf: Huruf, ukuran: f32, rata: Rata) {
+        let x = match rata {
+            Rata::Kiri => x,
+            Rata::Tengah => x - lebar_teks(teks, huruf, ukuran) / 2.0,
+            Rata::Kanan => x - lebar_teks(teks, huruf, ukuran),
+        };
+        self.tambah("BT", vec![]);
+        self.tambah("Tf", vec![huruf.nama().into(), ukuran.into()]);
+        self.tambah("Td", vec![x.into(), y.into()]);
+        self.tambah("Tj", vec![Object::String(win_ansi(teks), StringFormat::Literal)]);
+        self.tambah("ET", vec![]);
+    }
+
+    fn garis(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, tebal: f32) {
+        self.tambah("w", vec![tebal.into()]);
+        self.tambah("m", vec![x1.into(), y1.into()]);
+        self.tambah("l", vec![x2.into(), y2.into()]);
+        self.tambah("S", vec![]);
+    }
+
+    // Kotak dengan tepi kiri-atas (x, y); diisi warna jika ada, lalu diberi garis tepi
+    fn kotak(&mut self, x: f32, y: f32, lebar: f32, tinggi: f32, isi: Option<[f32; 3]>) {
+        let operand = || vec![x.into(), (y - tinggi).into(), lebar.into(), tinggi.into()];
+        if let Some(warna) = isi {
+            self.warna("rg", warna);
+            self.tambah("re", operand());
+            self.tambah("f", vec![]);
+            self.warna("rg", HITAM);
+        }
+        self.tambah("w", vec![0.5.into()]);
+        self.tambah("re", operand());
+        self.tambah("S", vec![]);
+    }
+
+    fn logo(&mut self, x: f32, y: f32, ukuran: f32) {
+        self.tambah("q", vec![]);
+        self.tambah("cm", vec![ukuran.into(), 0.into(), 0.into(), ukuran.into(), x.into(), y.into()]);
+        self.tambah("Do", vec!["Logo".into()]);
+        self.tambah("Q", vec![]);
+    }
+}
+
+// Kop surat + judul laporan
+fn kop(kanvas: &mut Kanvas, record: &KualitasAirRecord) {
+    let ukuran_logo = 62.0;
+    kanvas.logo(KIRI, ATAS - ukuran_logo + 6.0, ukuran_logo);
+
+    let tengah_kop = (KIRI + ukuran_logo + KANAN) / 2.0;
+    let mut y = ATAS - 12.0;
+    for (teks, huruf, ukuran) in KOP {
+        kanvas.teks(tengah_kop, y, teks, huruf, ukuran, Rata::Tengah);
+        y -= ukuran + 5.0;
+    }
+
+    let y_garis = ATAS - 64.0;
+    kanvas.garis(KIRI, y_garis, KANAN, y_garis, 2.0);
+    kanvas.garis(KIRI, y_garis - 3.0, KANAN, y_garis - 3.0, 0.5);
+
+    let tengah = (KIRI + KANAN) / 2.0;
+    kanvas.y = y_garis - 24.0;
+    kanvas.teks(tengah, kanvas.y, "LAPORAN HASIL ANALISA KUALITAS AIR", Huruf::Tebal, 13.0, Rata::Tengah);
+    kanvas.y -= 13.0;
+    kanvas.teks(tengah, kanvas.y, "Unit Kerja Hidrologi & Kualitas Air", Huruf::Biasa, 9.0, Rata::Tengah);
+    if let Some(id) = record.id {
+        kanvas.y -= 11.0;
+        kanvas.teks(tengah, kanvas.y, &format!("No. Data: {}", id), Huruf::Biasa, 8.5, Rata::Tengah);
+    }
+    kanvas.y -= 20.0;
+}
+
+// Identitas pos & sampling dalam dua kolom "Label : nilai"
+fn metadata(kanvas: &mut Kanvas, record: &KualitasAirRecord) {
+    let gabung = |bagian: &[Option<&str>]| -> String {
+        let isi: Vec<&str> = bagian.iter().flatten().copied().filter(|s| !s.trim().is_empty()).collect();
+        if isi.is_empty() { "-".to_string() } else { isi.join(" / ") }
+    };
+    let kiri = [
+        ("Nama Pos", gabung(&[record.nama_pos.as_deref()])),
+        ("DAS / Sungai", gabung(&[record.das.as_deref(), record.sungai.as_deref()])),
+        ("Wilayah Sungai", gabung(&[record.wilayah_sungai.as_deref()])),
+        ("Desa / Kecamatan", gabung(&[record.desa.as_deref(), record.kecamatan.as_deref()])),
+        ("Kabupaten / Provinsi", gabung(&[record.kabupaten.as_deref(), record.provinsi.as_deref()])),
+    ];
+    let kanan = [
+        ("Tanggal Sampling", record.tanggal_sampling.map(tanggal::format_panjang).unwrap_or_else(|| "-".to_string())),
+        ("Waktu Sampling", record.waktu_sampling.map(|w| w.format("%H:%M").to_string()).unwrap_or_else(|| "-".to_string())),
+        ("Pelaksana", gabung(&[record.pelaksana.as_deref()])),
+        ("Laboratorium", gabung(&[record.laboratorium.as_deref()])),
+        ("Koordinat", gabung(&[record.koordinat_geografis.as_deref()])),
+    ];
+
+    let ukuran = 9.0;
+    let lebar_label = 88.0;
+    let x_kanan = (KIRI + KANAN) / 2.0 + 10.0;
+    for ((label_kiri, isi_kiri), (label_kanan, isi_kanan)) in kiri.iter().zip(kanan.iter()) {
+        for (x, label, isi, batas) in [
+            (KIRI, label_kiri, isi_kiri, x_kanan - 10.0),
+            (x_kanan, label_kanan, isi_kanan, KANAN),
+        ] {
+            kanvas.teks(x, kanvas.y, label, Huruf::Biasa, ukuran, Rata::Kiri);
+            kanvas.teks(x + lebar_label, kanvas.y, ":", Huruf::Biasa, ukuran, Rata::Kiri);
+            let lebar_isi = batas - (x + lebar_label + 6.0);
+            let isi = potong(isi, Huruf::Tebal, ukuran, lebar_isi);
+            kanvas.teks(x + lebar_label + 6.0, kanvas.y, &isi, Huruf::Tebal, ukuran, Rata::Kiri);
+        }
+        kanvas.y -= 14.0;
+    }
+    kanvas.y -= 8.0;
+}
+
+fn judul_tabel(kanvas: &mut Kanvas) {
+    let tinggi = 18.0;
+    let mut x = KIRI;
+    for (judul, lebar) in KOLOM_TABEL {
+        kanvas.kotak(x, kanvas.y, lebar, tinggi, Some(ABU_JUDUL));
+        kanvas.teks(x + lebar / 2.0, kanvas.y - 12.0, judul, Huruf::Tebal, 8.5, Rata::Tengah);
+        x += lebar;
+    }
+    kanvas.y -= tinggi;
+}
+
+// Tabel parameter: hasil uji, baku mutu & tanda melampaui (mengikuti rincian perhitungan IP)
+fn tabel_parameter(kanvas: &mut Kanvas, data: &DataLaporan) {
+    let tinggi = 14.0;
+    let ukuran = 8.5;
+    let lebar_tabel: f32 = KOLOM_TABEL.iter().map(|(_, lebar)| lebar).sum();
+    let record = data.record;
+
+    judul_tabel(kanvas);
+    let mut nomor = 0;
+    for (kelompok, parameter) in KELOMPOK_PARAMETER {
+        // Judul kelompok tidak dibiarkan sendirian di dasar halaman
+        if kanvas.pastikan_ruang(tinggi * 2.0) {
+            judul_tabel(kanvas);
+        }
+        kanvas.kotak(KIRI, kanvas.y, lebar_tabel, tinggi, Some(ABU_KELOMPOK));
+        kanvas.teks(KIRI + 4.0, kanvas.y - 10.0, kelompok, Huruf::Tebal, ukuran, Rata::Kiri);
+        kanvas.y -= tinggi;
+
+        for &kunci in parameter.iter() {
+            nomor += 1;
+            if kanvas.pastikan_ruang(tinggi) {
+                judul_tabel(kanvas);
+            }
+
+            let sensor = record.tersensor.iter().find(|t| t.parameter == kunci);
+            let hasil = match (sensor, record.nilai_parameter(kunci)) {
+                (Some(t), _) => format!("{}{}", t.kualifier.simbol(), angka(t.batas_deteksi)),
+                (None, Some(nilai)) => angka(nilai),
+                (None, None) => "-".to_string(),
+            };
+            let rincian = data.hasil_ip.parameter.iter().find(|r| r.parameter == kunci);
+            let batas = data.baku_mutu.iter().find(|b| b.parameter == kunci);
+            let melampaui = rincian.is_some_and(|r| r.melampaui);
+            let keterangan = match rincian {
+                Some(r) if r.melampaui => "Melampaui",
+                Some(_) => "Memenuhi",
+                None => "",
+            };
+
+            let isi = [
+                nomor.to_string(),
+                label_parameter(kunci).unwrap_or(kunci).to_string(),
+                satuan_parameter(kunci).unwrap_or("-").to_string(),
+                if melampaui { format!("{} *", hasil) } else { hasil },
+                teks_baku_mutu(batas),
+                keterangan.to_string(),
+            ];
+
+            let mut x = KIRI;
+            for (kol, ((_, lebar), teks)) in KOLOM_TABEL.iter().zip(isi.iter()).enumerate() {
+                kanvas.kotak(x, kanvas.y, *lebar, tinggi, None);
+                let (huruf, warna) = match kol {
+                    3 | 5 if melampaui => (Huruf::Tebal, MERAH),
+                    _ => (Huruf::Biasa, HITAM),
+                };
+                let teks = potong(teks, huruf, ukuran, lebar - 6.0);
+                let y = kanvas.y - 10.0;
+                kanvas.warna("rg", warna);
+                match kol {
+                    1 => kanvas.teks(x + 4.0, y, &teks, huruf, ukuran, Rata::Kiri),
+                    _ => kanvas.teks(x + lebar / 2.0, y, &teks, huruf, ukuran, Rata::Tengah),
+                }
+                kanvas.warna("rg", HITAM);
+                x += lebar;
+            }
+            kanvas.y -= tinggi;
+        }
+    }
+    kanvas.y -= 12.0;
+}
+
+// Kotak nilai IP & status mutu, lalu keterangan tanda & nilai tersensor
+fn ringkasan_ip(kanvas: &mut Kanvas, data: &DataLaporan) {
+    let record = data.record;
+    let hasil = data.hasil_ip;
+    let tinggi = 38.0;
+    kanvas.pastikan_ruang(tinggi + 60.0);
+
+    let warna_status = if hasil.status.starts_with("Cemar") { MERAH } else { HITAM };
+    kanvas.kotak(KIRI, kanvas.y, KANAN - KIRI, tinggi, None);
+    let y = kanvas.y - 15.0;
+    kanvas.teks(KIRI + 8.0, y, "Indeks Pencemaran (IP):", Huruf::Tebal, 10.0, Rata::Kiri);
+    kanvas.teks(KIRI + 135.0, y, &format!("{:.2}", hasil.nilai_ip), Huruf::Tebal, 10.0, Rata::Kiri);
+    kanvas.teks(KANAN - 150.0, y, "Status Mutu:", Huruf::Tebal, 10.0, Rata::Kanan);
+    kanvas.warna("rg", warna_status);
+    kanvas.teks(KANAN - 8.0, y, &hasil.status, Huruf::Tebal, 10.0, Rata::Kanan);
+    kanvas.warna("rg", HITAM);
+    let rujukan = format!("Baku mutu: {} - metode Indeks Pencemaran (Kepmen LH No. 115 Tahun 2003)", data.nama_baku_mutu);
+    kanvas.teks(KIRI + 8.0, y - 14.0, &potong(&rujukan, Huruf::Biasa, 8.0, KANAN - KIRI - 16.0), Huruf::Biasa, 8.0, Rata::Kiri);
+    kanvas.y -= tinggi + 14.0;
+
+    let mut catatan = vec![
+        "* Hasil uji melampaui baku mutu.".to_string(),
+        format!(
+            "Temperatur dinilai sebagai deviasi dari suhu alamiah {} °C.",
+            angka(hasil.suhu_alamiah)
+        ),
+    ];
+    if !record.tersensor.is_empty() {
+        catatan.push(format!(
+            "< / > : di bawah / di atas batas deteksi (kebijakan perhitungan IP: {}).",
+            hasil.kebijakan_sensor.label()
+        ));
+    }
+    kanvas.teks(KIRI, kanvas.y, "Keterangan:", Huruf::Tebal, 8.0, Rata::Kiri);
+    for teks in catatan {
+        kanvas.y -= 11.0;
+        kanvas.teks(KIRI + 8.0, kanvas.y, &teks, Huruf::Biasa, 8.0, Rata::Kiri);
+    }
+    kanvas.y -= 24.0;
+}
+
+// Kolom tanda tangan, sama dengan lembar cetak
+fn tanda_tangan(kanvas: &mut Kanvas) {
+    kanvas.pastikan_ruang(90.0);
+    let kolom = [
+        (KIRI + 100.0, ["Mengetahui,", "Kepala Unit"]),
+        (KANAN - 100.0, ["", "Petugas Sampling"]),
+    ];
+    for (x, baris) in kolom {
+        let mut y = kanvas.y;
+        for teks in baris {
+            kanvas.teks(x, y, teks, Huruf::Biasa, 9.0, Rata::Tengah);
+            y -= 12.0;
+        }
+        kanvas.teks(x, y - 50.0, "( .................................. )", Huruf::Biasa, 9.0, Rata::Tengah);
+    }
+    kanvas.y -= 80.0;
+}
+
+// Nomor halaman & tanggal cetak di kaki setiap halaman
+fn kaki_halaman(kanvas: &mut Kanvas, tanggal_cetak: NaiveDate) {
+    let jumlah = kanvas.halaman.len();
+    let semua = std::mem::take(&mut kanvas.halaman);
+    for (idx, ops) in semua.into_iter().enumerate() {
+        kanvas.halaman.push(ops);
+        let y = BAWAH - 25.0;
+        kanvas.garis(KIRI, y + 10.0, KANAN, y + 10.0, 0.3);
+        let dicetak = format!("Dicetak: {}", tanggal::format_panjang(tanggal_cetak));
+        kanvas.teks(KIRI, y, &dicetak, Huruf::Biasa, 7.5, Rata::Kiri);
+        kanvas.teks(KANAN, y, &format!("Halaman {} dari {}", idx + 1, jumlah), Huruf::Biasa, 7.5, Rata::Kanan);
+    }
+}
+
+// Rakit dokumen PDF: font standar Helvetica (WinAnsi), logo JPEG apa adanya (DCTDecode)
+fn susun_pdf(kanvas: Kanvas, judul: &str) -> Result<Vec<u8>, String> {
+    let (lebar_logo, tinggi_logo, komponen) = info_jpeg(LOGO)
+        .ok_or_else(|| "Gagal membaca logo laporan".to_string())?;
+    let ruang_warna = match komponen {
+        1 => "DeviceGray",
+        3 => "DeviceRGB",
+        _ => return Err("Logo laporan harus berformat JPEG RGB / grayscale".to_string()),
+    };
+
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+
+    let font = |nama: &str| dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => nama,
+        "Encoding" => "WinAnsiEncoding",
+    };
+    let biasa_id = doc.add_object(font("Helvetica"));
+    let tebal_id = doc.add_object(font("Helvetica-Bold"));
+    let logo_id = doc.add_object(Stream::new(dictionary! {
+        "Type" => "XObject",
+        "Subtype" => "Image",
+        "Width" => lebar_logo as i64,
+        "Height" => tinggi_logo as i64,
+        "ColorSpace" => ruang_warna,
+        "BitsPerComponent" => 8,
+        "Filter" => "DCTDecode",
+    }, LOGO.to_vec()).with_compression(false));
+    let resources_id = doc.add_object(dictionary! {
+        "Font" => dictionary! { "F1" => biasa_id, "F2" => tebal_id },
+        "XObject" => dictionary! { "Logo" => logo_id },
+    });
+
+    let mut kids: Vec<Object> = Vec::new();
+    for operations in kanvas.halaman {
+        let konten = Content { operations }.encode()
+            .map_err(|e| format!("Gagal menyusun halaman PDF: {}", e))?;
+        let content_id = doc.add_object(Stream::new(dictionary! {}, konten));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Contents" => content_id,
+        });
+        kids.push(page_id.into());
+    }
+
+    let jumlah = kids.len() as i64;
+    doc.objects.insert(pages_id, Object::Dictionary(dictionary! {
+        "Type" => "Pages",
+        "Kids" => kids,
+        "Count" => jumlah,
+        "Resources" => resources_id,
+        "MediaBox" => vec![0.into(), 0.into(), LEBAR_HALAMAN.into(), TINGGI_HALAMAN.into()],
+    }));
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    let info_id = doc.add_object(dictionary! {
+        "Title" => Object::String(win_ansi(judul), StringFormat::Literal),
+        "Producer" => Object::string_literal("Data Hidrologi"),
+    });
+    doc.trailer.set("Root", catalog_id);
+    doc.trailer.set("Info", info_id);
+    doc.compress();
+
+    let mut hasil = Vec::new();
+    doc.save_to(&mut hasil)
+        .map_err(|e| format!("Gagal menyusun file PDF: {}", e))?;
+    Ok(hasil)
+}
+
+/// Susun "Laporan Hasil Analisa Kualitas Air" satu sampel dalam bentuk PDF (A4)
+pub fn buat_laporan(data: &DataLaporan) -> Result<Vec<u8>, String> {
+    let mut kanvas = Kanvas::baru();
+    kop(&mut kanvas, data.record);
+    metadata(&mut kanvas, data.record);
+    tabel_parameter(&mut kanvas, data);
+    ringkasan_ip(&mut kanvas, data);
+    tanda_tangan(&mut kanvas);
+    kaki_halaman(&mut kanvas, data.tanggal_cetak);
+
+    let judul = format!(
+        "Laporan Hasil Analisa Kualitas Air - {}",
+        data.record.nama_pos.as_deref().unwrap_or("-")
+    );
+    susun_pdf(kanvas, &judul)
+}
+
+/// Simpan laporan PDF ke path yang dipilih pengguna
+pub fn simpan_laporan(data: &DataLaporan, file_path: &str) -> Result<(), String> {
+    let pdf = buat_laporan(data)?;
+    std::fs::write(file_path, pdf)
+        .map_err(|e| format!("Gagal menyimpan file PDF: {}", e))
+}
@@ -1,5 +1,16 @@
 pub mod db_service;
 pub mod ip_calc;
-// pub mod storet_calc;
+pub mod baku_mutu;
+pub mod suhu_alamiah;
+pub mod pos_service;
+pub mod nilai_tersensor;
+pub mod storet_calc;
 pub mod pdf_engine;
-pub mod csv_service;
\ No newline at end of file
+pub mod template_lab;
+pub mod csv_service;
+pub mod satuan;
+pub mod tanggal;
+pub mod query_kualitas_air;
+pub mod impor_tabel;
+pub mod xlsx_service;
+pub mod laporan_pdf;
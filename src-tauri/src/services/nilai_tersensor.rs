@@ -0,0 +1,71 @@
+use crate::models::kualitas_air::KualitasAirRecord;
+use crate::models::nilai_tersensor::{Kualifier, NilaiTersensor};
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use std::collections::HashMap;
+
+/// Tulis ulang seluruh nilai tersensor milik satu sampel
+pub async fn simpan(
+    tx: &mut Transaction<'_, Sqlite>,
+    kualitas_air_id: i64,
+    tersensor: &[NilaiTersensor]
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM nilai_tersensor WHERE kualitas_air_id = ?")
+        .bind(kualitas_air_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| format!("Gagal menyimpan nilai tersensor: {}", e))?;
+
+    let sql = "
+        INSERT INTO nilai_tersensor (kualitas_air_id, parameter, kualifier, batas_deteksi)
+        VALUES ($1, $2, $3, $4)
+    ";
+
+    for sensor in tersensor {
+        sqlx::query(sql)
+            .bind(kualitas_air_id)
+            .bind(&sensor.parameter)
+            .bind(sensor.kualifier)
+            .bind(sensor.batas_deteksi)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| format!("Gagal menyimpan nilai tersensor {}: {}", sensor.parameter, e))?;
+    }
+
+    Ok(())
+}
+
+/// Isi field `tersensor` pada record hasil query (tabel nilai_tersensor tidak ikut di-join)
+pub async fn lengkapi(pool: &SqlitePool, records: &mut [KualitasAirRecord]) -> Result<(), String> {
+    if records.is_empty() {
+        return Ok(());
+    }
+
+    // Hanya ambil baris milik record yang diminta (daftar id dikirim sebagai array JSON)
+    let ids: Vec<i64> = records.iter().filter_map(|r| r.id).collect();
+    let ids = serde_json::to_string(&ids).map_err(|e| format!("Gagal menyusun id sampel: {}", e))?;
+
+    let rows: Vec<(i64, String, Kualifier, f64)> = sqlx::query_as(
+        "SELECT kualitas_air_id, parameter, kualifier, batas_deteksi FROM nilai_tersensor
+         WHERE kualitas_air_id IN (SELECT value FROM json_each($1))
+         ORDER BY id"
+    )
+        .bind(ids)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Gagal mengambil nilai tersensor: {}", e))?;
+
+    let mut per_sampel: HashMap<i64, Vec<NilaiTersensor>> = HashMap::new();
+    for (kualitas_air_id, parameter, kualifier, batas_deteksi) in rows {
+        per_sampel.entry(kualitas_air_id)
+            .or_default()
+            .push(NilaiTersensor { parameter, kualifier, batas_deteksi });
+    }
+
+    for record in records.iter_mut() {
+        if let Some(tersensor) = record.id.and_then(|id| per_sampel.remove(&id)) {
+            record.tersensor = tersensor;
+        }
+    }
+
+    Ok(())
+}
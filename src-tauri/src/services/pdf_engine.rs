@@ -1,163 +1,563 @@
-use crate::models::kualitas_air::KualitasAirRecord;
+use crate::models::import_pdf::{BarisTeks, HasilEkstraksi, HasilImportPdf, LaporanEkstraksi, SumberField};
+use crate::models::kualitas_air::{satuan_parameter, KualitasAirRecord, NAMA_PARAMETER};
+use crate::models::nilai_tersensor::{Kualifier, NilaiTersensor};
+use crate::models::template_lab::{AliasParameter, PolaHeader, TemplateLab};
+use crate::services::satuan::{self, Konversi};
+use crate::services::tanggal;
+use crate::services::template_lab;
+use chrono::Datelike;
 use regex::Regex;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
 
-pub fn parse_pdf(file_path: String) -> Result<KualitasAirRecord, String> {
+// Tingkat keyakinan ekstraksi (0.0 - 1.0)
+const KEYAKINAN_TINGGI: f64 = 0.9;   // Angka tepat di kolom nilai sebelum penanda metode
+const KEYAKINAN_SENSOR: f64 = 0.7;   // Nilai "<" / ">" (batas deteksi), nilai asli tidak diketahui
+const KEYAKINAN_RENDAH: f64 = 0.5;   // Ditemukan lebih dari sekali / sumber kurang tepat
+const KEYAKINAN_IN_SITU: f64 = 0.7;  // Baris ukur lapangan tanpa kolom metode
+
+// Posisi akhir (byte) kata kunci pada baris. Jika utuh = true, karakter sebelum
+// dan sesudahnya tidak boleh huruf/angka.
+fn posisi_kata(text: &str, kata: &str, utuh: bool) -> Option<usize> {
+    text.match_indices(kata)
+        .find(|&(idx, _)| {
+            if !utuh { return true; }
+            let sebelum = text[..idx].chars().next_back();
+            let sesudah = text[idx + kata.len()..].chars().next();
+            !sebelum.is_some_and(char::is_alphanumeric) && !sesudah.is_some_and(char::is_alphanumeric)
+        })
+        .map(|(idx, _)| idx + kata.len())
+}
+
+// Kata kunci alias yang cocok dengan baris beserta posisi akhirnya (None jika alias tidak berlaku)
+fn cocok<'a>(alias: &'a AliasParameter, text: &str) -> Option<(&'a str, usize)> {
+    if !alias.wajib.iter().all(|k| text.contains(k.as_str())) { return None; }
+    if alias.kecuali.iter().any(|k| text.contains(k.as_str())) { return None; }
+    alias.kata_kunci.iter()
+        .find_map(|k| posisi_kata(text, k, alias.kata_utuh).map(|akhir| (k.as_str(), akhir)))
+}
+
+// Catat asal nilai satu field. Field yang ditemukan ulang menimpa catatan lama
+// dengan keyakinan rendah (nilai terakhir yang dipakai, sama seperti record).
+fn catat(laporan: &mut LaporanEkstraksi, mut sumber: SumberField) {
+    if let Some(idx) = laporan.field.iter().position(|f| f.field == sumber.field) {
+        laporan.field.remove(idx);
+        sumber.keyakinan = sumber.keyakinan.min(KEYAKINAN_RENDAH);
+        sumber.catatan = Some("Ditemukan lebih dari sekali, nilai terakhir dipakai".to_string());
+    }
+    laporan.field.push(sumber);
+}
+
+fn sumber(field: &str, nomor_baris: usize, baris: &str, kata_kunci: &str, keyakinan: f64, catatan: Option<&str>) -> SumberField {
+    SumberField {
+        field: field.to_string(),
+        nomor_baris,
+        baris: baris.to_string(),
+        kata_kunci: kata_kunci.to_string(),
+        satuan: None,
+        keyakinan,
+        catatan: catatan.map(str::to_string),
+    }
+}
+
+/// Ambil teks mentah dari file PDF (tahap pertama, terpisah dari parsing)
+pub fn extract_text(file_path: &str) -> Result<String, String> {
     println!("📂 Membaca PDF dari: {}", file_path);
-    let content = pdf_extract::extract_text(&file_path)
-        .map_err(|e| format!("Gagal membaca PDF: {}", e))?;
-
-    println!("\n=== MULAI EKSTRAKSI (GLOBAL SEARCH PATTERN) ===");
-
-    let mut data = KualitasAirRecord {
-        id: None,
-        nama_pos: None, das: None, wilayah_sungai: None, provinsi: None, kabupaten: None,
-        tahun: None, elevasi_pos: None, pelaksana: None, kecamatan: None, laboratorium: None,
-        sungai: None, desa: None, koordinat_geografis: None,
-        tanggal_sampling: None, waktu_sampling: None,
-        
-        temperatur: None, konduktivitas: None, kekeruhan: None, oksigen: None, ph: None,
-        tds: None, tss: None, warna: None, klorida: None, amoniak: None, nitrat: None,
-        nitrit: None, fosfat: None, deterjen: None, arsen: None, besi: None, mangan: None,
-        tembaga: None, merkuri: None, sianida: None, fluorida: None, belerang: None,
-        cod: None, bod: None, minyak_dan_lemak: None, fenol: None, total_coliform: None,
-        debit: None,
-        
-        nilai_ip: None, status_ip: None, nilai_storet: None, status_storet: None,
-        created_at: None,
+    pdf_extract::extract_text(file_path)
+        .map_err(|e| format!("Gagal membaca PDF: {}", e))
+}
+
+/// Baca PDF lalu ekstrak dengan template lab yang terdeteksi otomatis.
+/// Satu record per kolom sampel (laporan biasa: satu record).
+pub fn parse_pdf(file_path: String, templates: &[TemplateLab]) -> Result<Vec<HasilEkstraksi>, String> {
+    let content = extract_text(&file_path)?;
+    let template = template_lab::detect(templates, &content);
+    parse_text(&content, template)
+}
+
+/// Ekstrak record dari teks laporan sesuai satu template lab.
+/// Laporan multi-sampel (satu kolom per titik/tanggal sampling) menghasilkan satu record per kolom;
+/// laporan biasa menghasilkan tepat satu record.
+pub fn parse_text(content: &str, template: &TemplateLab) -> Result<Vec<HasilEkstraksi>, String> {
+    println!("\n=== MULAI EKSTRAKSI (TEMPLATE: {}) ===", template.nama);
+
+    let mut data = KualitasAirRecord::default();
+    let mut laporan = LaporanEkstraksi { template: template.nama.clone(), ..Default::default() };
+
+    // Nomor baris dimulai dari 1 agar mudah dicocokkan dengan teks PDF
+    let lines: Vec<(usize, &str)> = content.lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .collect();
+
+    // --- STRATEGI 1: METADATA HEADER (POLA REGEX TEMPLATE) ---
+    // Pola yang ditulis lebih dulu diutamakan; field yang sudah terisi tidak ditimpa pola berikutnya.
+    let mut terisi: Vec<&str> = Vec::new();
+    for pola in &template.header {
+        if terisi.contains(&pola.field.as_str()) { continue; }
+        let re = Regex::new(&pola.pola)
+            .map_err(|e| format!("Pola header {} tidak valid: {}", pola.field, e))?;
+
+        for &(nomor, text) in &lines {
+            let Some(caps) = re.captures(text) else { continue };
+            let mentah = caps.name("nilai").or_else(|| caps.get(0)).map_or("", |m| m.as_str());
+            let Some(nilai) = nilai_header(pola, mentah) else { continue };
+
+            println!("[META] {} : {}", pola.field, nilai);
+            if isi_metadata(&mut data, &pola.field, nilai) {
+                terisi.push(&pola.field);
+                catat(&mut laporan, sumber(&pola.field, nomor, text, &pola.pola, pola.keyakinan, pola.catatan.as_deref()));
+            }
+            break;
+        }
+    }
+
+    // --- STRATEGI 1B: JUDUL KOLOM SAMPEL (LAPORAN MULTI-SAMPEL) ---
+    // Setiap kolom sampel mendapat salinan metadata umum, lalu pos/tanggal/waktu miliknya sendiri.
+    let kolom = kolom_sampel(&lines, template)?;
+    let jumlah_sampel = kolom.iter().map(|k| k.nilai.len()).max().unwrap_or(1);
+    let jumlah_sampel = if jumlah_sampel > 1 && ada_baris_multi_nilai(&lines, template, jumlah_sampel) {
+        println!("[MULTI] {} kolom sampel terdeteksi", jumlah_sampel);
+        jumlah_sampel
+    } else {
+        1
     };
 
-    let lines: Vec<&str> = content.lines().collect();
+    let mut hasil: Vec<HasilEkstraksi> = (0..jumlah_sampel)
+        .map(|_| HasilEkstraksi { record: data.clone(), laporan: laporan.clone() })
+        .collect();
 
-    // --- STRATEGI 1: PENCARIAN METADATA GLOBAL ---
-    
-    for line in &lines {
-        let text = line.trim();
-        if text.is_empty() { continue; }
+    if jumlah_sampel > 1 {
+        for k in kolom.iter().filter(|k| k.nilai.len() == jumlah_sampel) {
+            for (ke, (item, nilai)) in hasil.iter_mut().zip(&k.nilai).enumerate() {
+                if !isi_metadata(&mut item.record, &k.pola.field, nilai.clone()) { continue; }
+                let catatan = format!("Kolom sampel ke-{}", ke + 1);
+                let asal = SumberField { catatan: Some(catatan), ..sumber(&k.pola.field, k.nomor_baris, k.baris, &k.pola.pola, k.pola.keyakinan, None) };
+                // Catatan lama (metadata umum) diganti, bukan dianggap temuan ganda
+                item.laporan.field.retain(|f| f.field != k.pola.field);
+                catat(&mut item.laporan, asal);
+            }
+        }
+    }
 
-        // 1. CARI LOKASI (Keyword: "AWLR")
-        if text.contains("AWLR") {
-            let val = text.replace(":", "").trim().to_string();
-            println!("[META] Lokasi Ditemukan: {}", val);
-            data.nama_pos = Some(val);
+    // Tahun pemantauan mengikuti tanggal sampling
+    for HasilEkstraksi { record, laporan } in hasil.iter_mut() {
+        if let Some(tanggal) = record.tanggal_sampling {
+            record.tahun = Some(tanggal.year());
+            if let Some(asal) = laporan.field.iter().find(|f| f.field == "tanggal_sampling") {
+                let asal = SumberField { field: "tahun".to_string(), catatan: Some("Diturunkan dari tanggal sampling".to_string()), ..asal.clone() };
+                catat(laporan, asal);
+            }
         }
+    }
+
+    // --- STRATEGI 2: PARAMETER (KOLOM METODE) ---
+    for &(nomor, text) in &lines {
+        let mut terpetakan = false;
+
+        let alias = template.parameter.iter()
+            .find_map(|alias| cocok(alias, text).map(|(kata_kunci, akhir)| (alias, kata_kunci, akhir)));
+
+        if let Some((alias, kata_kunci, akhir)) = alias {
+            let field = alias.field.as_str();
+            let ada_metode = posisi_metode(text, template).is_some();
+
+            // Baris bermetode: kolom sebelum penanda metode (satu nilai per kolom sampel).
+            // Baris in-situ tanpa metode: angka pertama setelah kata kunci, tidak menimpa nilai yang sudah ada
+            // di sampel masing-masing.
+            let nilai = if ada_metode {
+                extract_sni_value(text, field, template, jumlah_sampel)
+            } else if alias.in_situ && hasil.iter().any(|item| item.record.nilai_parameter(field).is_none()) {
+                extract_in_situ_value(&text[akhir..], field, jumlah_sampel)
+            } else {
+                Vec::new()
+            };
 
-        // 2. CARI KOORDINAT (Keyword: "S:" dan "E:" dan "°")
-        if text.contains("S:") && text.contains("E:") && text.contains("°") {
-             // FIX: Menghapus variabel 'val' yang tidak terpakai
-             let clean_coord = text.replace("Titik Koordinat", "").trim().to_string();
-             println!("[META] Koordinat Ditemukan: {}", clean_coord);
-             data.koordinat_geografis = Some(clean_coord);
+            for (item, nilai) in hasil.iter_mut().zip(nilai) {
+                if !ada_metode && item.record.nilai_parameter(field).is_some() { continue; }
+                let asal = Asal { field, nomor, text, kata_kunci, ada_metode };
+                terpetakan |= isi_parameter(item, asal, nilai);
+            }
         }
 
-        // 3. CARI TANGGAL (Pola Regex)
-        if data.tanggal_sampling.is_none() {
-            let re_date = Regex::new(r"(\d{1,2})\s+([A-Za-z]{3,})\s+(\d{4})").unwrap();
-            
-            if let Some(caps) = re_date.captures(text) {
-                let is_sampling = text.contains("Tgl sampling");
-                let is_penerimaan = text.contains("Tgl Penerimaan");
-                
-                if is_sampling || is_penerimaan {
-                    let raw_date = caps.get(0).unwrap().as_str();
-                    if let Some(formatted) = convert_indo_date(raw_date) {
-                         println!("[META] Tanggal Ditemukan ({}) : {} -> {}", 
-                            if is_sampling { "Sampling" } else { "Penerimaan" }, 
-                            raw_date, formatted);
-                         data.tanggal_sampling = Some(formatted);
-                    }
-                }
+        // Baris hasil uji (bermetode uji) yang tidak menghasilkan nilai parameter
+        if !terpetakan && posisi_metode(text, template).is_some() {
+            for item in hasil.iter_mut() {
+                item.laporan.sni_tidak_terpetakan.push(BarisTeks { nomor_baris: nomor, baris: text.to_string() });
+            }
+        }
+    }
+
+    println!("=== SELESAI ({} sampel, {} field, {} baris SNI tidak terpetakan) ===\n",
+        hasil.len(), hasil[0].laporan.field.len(), hasil[0].laporan.sni_tidak_terpetakan.len());
+    Ok(hasil)
+}
+
+// Nilai header setelah dibersihkan (teks hapus) dan dinormalisasi.
+// Tanggal dinormalisasi ke "YYYY-MM-DD", waktu ke "HH:MM"; None jika tidak bisa dibaca.
+fn nilai_header(pola: &PolaHeader, mentah: &str) -> Option<String> {
+    let mut nilai = mentah.to_string();
+    for buang in &pola.hapus {
+        nilai = nilai.replace(buang.as_str(), "");
+    }
+    let nilai = nilai.trim();
+    if nilai.is_empty() { return None; }
+
+    match pola.field.as_str() {
+        "tanggal_sampling" => tanggal::parse_tanggal(nilai).map(|d| d.format("%Y-%m-%d").to_string()),
+        "waktu_sampling" => tanggal::parse_waktu(nilai).map(|t| t.format("%H:%M").to_string()),
+        _ => Some(nilai.to_string()),
+    }
+}
+
+// Isi satu field metadata dari nilai header yang sudah dinormalisasi (false jika field tidak dikenal)
+fn isi_metadata(data: &mut KualitasAirRecord, field: &str, nilai: String) -> bool {
+    match field {
+        "tanggal_sampling" => {
+            data.tanggal_sampling = tanggal::parse_tanggal(&nilai);
+            data.tanggal_sampling.is_some()
+        }
+        "waktu_sampling" => {
+            data.waktu_sampling = tanggal::parse_waktu(&nilai);
+            data.waktu_sampling.is_some()
+        }
+        _ => match data.metadata_mut(field) {
+            Some(slot) => {
+                *slot = Some(nilai);
+                true
             }
+            None => false,
+        },
+    }
+}
+
+// Satu baris judul kolom sampel beserta nilai per kolom
+struct KolomHeader<'a> {
+    pola: &'a PolaHeader,
+    nomor_baris: usize,
+    baris: &'a str,
+    nilai: Vec<String>,
+}
+
+// Baris judul kolom sampel (pola header_sampel template), satu per field.
+// Nilai antar kolom dipisahkan minimal dua spasi. Baris yang salah satu kolomnya
+// memuat ":" dianggap label lain di baris yang sama (bukan kolom sampel) dan dilewati.
+fn kolom_sampel<'a>(lines: &[(usize, &'a str)], template: &'a TemplateLab) -> Result<Vec<KolomHeader<'a>>, String> {
+    let pemisah = Regex::new(r"\s{2,}").map_err(|e| e.to_string())?;
+    let mut kolom: Vec<KolomHeader> = Vec::new();
+
+    for pola in &template.header_sampel {
+        if kolom.iter().any(|k| k.pola.field == pola.field) { continue; }
+        let re = Regex::new(&pola.pola)
+            .map_err(|e| format!("Pola header sampel {} tidak valid: {}", pola.field, e))?;
+
+        for &(nomor, text) in lines {
+            let Some(caps) = re.captures(text) else { continue };
+            let mentah = caps.name("nilai").or_else(|| caps.get(0)).map_or("", |m| m.as_str());
+
+            let potongan: Vec<&str> = pemisah.split(mentah.trim()).collect();
+            if potongan.iter().any(|p| p.contains(':')) { continue; }
+
+            // Seluruh kolom harus valid (misal tanggal yang dikenali), jika tidak baris dilewati
+            let Some(nilai) = potongan.iter().map(|p| nilai_header(pola, p)).collect::<Option<Vec<_>>>() else { continue };
+            kolom.push(KolomHeader { pola, nomor_baris: nomor, baris: text, nilai });
+            break;
         }
     }
 
+    Ok(kolom)
+}
+
+// Konfirmasi tata letak multi-sampel: minimal satu baris parameter punya nilai sebanyak kolom sampel
+fn ada_baris_multi_nilai(lines: &[(usize, &str)], template: &TemplateLab, jumlah: usize) -> bool {
+    lines.iter().any(|&(_, text)| {
+        template.parameter.iter().find(|alias| cocok(alias, text).is_some())
+            .is_some_and(|alias| !extract_sni_value(text, &alias.field, template, jumlah).is_empty())
+    })
+}
+
+// Asal satu nilai parameter (untuk laporan ekstraksi)
+struct Asal<'a> {
+    field: &'a str,
+    nomor: usize,
+    text: &'a str,
+    kata_kunci: &'a str,
+    ada_metode: bool,
+}
+
+// Isi satu nilai parameter ke record dan catat asalnya. Mengembalikan true jika field dikenal.
+fn isi_parameter(item: &mut HasilEkstraksi, asal: Asal, nilai: NilaiBaris) -> bool {
+    let Asal { field, nomor, text, kata_kunci, ada_metode } = asal;
+    let HasilEkstraksi { record: data, laporan } = item;
+    let Some(slot) = data.parameter_mut(field) else { return false };
 
-    // --- STRATEGI 2: PARAMETER (LOGIKA SNI) ---
-    for line in &lines {
-        let text = line.trim();
-        if text.is_empty() { continue; }
-
-        if text.contains("TSS") || text.contains("Zat Padat Tersuspensi") { data.tss = extract_sni_value(text, "TSS"); }
-        else if text.contains("TDS") || text.contains("Zat Padat Terlarut") { data.tds = extract_sni_value(text, "TDS"); }
-        else if text.contains("Warna") { data.warna = extract_sni_value(text, "Warna"); }
-        else if text.contains("Temperatur") || text.contains("Suhu") { data.temperatur = extract_sni_value(text, "Temperatur"); }
-
-        else if text.contains("Amoniak") { data.amoniak = extract_sni_value(text, "Amoniak"); }
-        else if text.contains("Nitrat") && !text.contains("Nitrit") { data.nitrat = extract_sni_value(text, "Nitrat"); }
-        else if text.contains("Nitrit") { data.nitrit = extract_sni_value(text, "Nitrit"); }
-        else if text.contains("COD") { data.cod = extract_sni_value(text, "COD"); }
-        else if text.contains("BOD") { data.bod = extract_sni_value(text, "BOD"); }
-        else if text.contains("Detergen") || text.contains("Deterjen") { data.deterjen = extract_sni_value(text, "Deterjen"); }
-        else if text.contains("Minyak") && text.contains("lemak") { data.minyak_dan_lemak = extract_sni_value(text, "Minyak Lemak"); }
-        else if text.contains("Fenol") { data.fenol = extract_sni_value(text, "Fenol"); }
-        else if text.contains("Sianida") { data.sianida = extract_sni_value(text, "Sianida"); }
-        else if text.contains("Fluorida") { data.fluorida = extract_sni_value(text, "Fluorida"); }
-        else if text.contains("Klorida") { data.klorida = extract_sni_value(text, "Klorida"); }
-        else if text.contains("Besi") { data.besi = extract_sni_value(text, "Besi"); }
-        else if text.contains("Fosfat") { data.fosfat = extract_sni_value(text, "Fosfat"); }
-        else if text.contains("Tembaga") { data.tembaga = extract_sni_value(text, "Tembaga"); }
-        else if text.contains("Mangan") { data.mangan = extract_sni_value(text, "Mangan"); }
-        else if text.contains("Arsen") { data.arsen = extract_sni_value(text, "Arsen"); }
-        else if text.contains("Merkuri") { data.merkuri = extract_sni_value(text, "Merkuri"); }
-        else if text.contains("Belerang") || text.contains("H2S") { data.belerang = extract_sni_value(text, "Belerang"); }
-
-        else if text.contains("Total Coliform") || text.contains("Koliform") { data.total_coliform = extract_sni_value(text, "Total Coliform"); }
-    }
-
-    println!("=== SELESAI ===\n");
-    Ok(data)
-}
-
-fn convert_indo_date(raw_date: &str) -> Option<String> {
-    let parts: Vec<&str> = raw_date.split_whitespace().collect();
-    if parts.len() < 3 { return None; }
-
-    let day = parts[0];
-    let month_str = parts[1].to_lowercase();
-    let year = parts[2];
-
-    let month_num = match month_str.as_str() {
-        "januari" | "jan" => "01",
-        "februari" | "feb" => "02",
-        "maret" | "mar" => "03",
-        "april" | "apr" => "04",
-        "mei" | "may" => "05",
-        "juni" | "jun" => "06",
-        "juli" | "jul" => "07",
-        "agustus" | "agu" | "aug" => "08",
-        "september" | "sep" => "09",
-        "oktober" | "okt" | "oct" => "10",
-        "november" | "nov" => "11",
-        "desember" | "des" | "dec" => "12",
-        _ => return None, 
+    // Samakan ke satuan baku parameter
+    let konversi = satuan::ke_satuan_baku(field, nilai.nilai, nilai.satuan.as_deref());
+    let angka = match konversi {
+        Konversi::Baku(v) | Konversi::Dikonversi { nilai: v, .. } | Konversi::TidakDikenal(v) => v,
     };
-    Some(format!("{}-{}-{}", day, month_num, year))
-}
-
-fn extract_sni_value(raw_line: &str, param_name: &str) -> Option<f64> {
-    let split_idx = raw_line.to_uppercase().find("SNI");
-    match split_idx {
-        Some(idx) => {
-            let left_part = &raw_line[..idx].trim();
-            let parts: Vec<&str> = left_part.split_whitespace().collect();
-            if let Some(last_word) = parts.last() {
-                let mut is_less_than = last_word.contains('<');
-                if !is_less_than && parts.len() >= 2 {
-                    if let Some(prev_word) = parts.get(parts.len() - 2) {
-                        if prev_word.contains('<') { is_less_than = true; }
-                    }
-                }
-                let clean_str = last_word.replace(',', ".").replace("<", "").replace(">", "");
-                match clean_str.parse::<f64>() {
-                    Ok(mut val) => {
-                        if is_less_than { val = val * 0.99; }
-                        println!("[OK] {}: {}", param_name, val);
-                        return Some(val);
-                    },
-                    Err(_) => {}
+    *slot = Some(angka);
+
+    // Nilai tersensor: angka di record = batas deteksi, kualifier dicatat terpisah
+    data.tersensor.retain(|t| t.parameter != field);
+    if let Some(kualifier) = nilai.kualifier {
+        data.tersensor.push(NilaiTersensor { parameter: field.to_string(), kualifier, batas_deteksi: angka });
+    }
+
+    let mut asal = if nilai.kualifier.is_some() {
+        sumber(field, nomor, text, kata_kunci, KEYAKINAN_SENSOR, Some("Nilai di luar batas deteksi (< / >)"))
+    } else if !ada_metode {
+        sumber(field, nomor, text, kata_kunci, KEYAKINAN_IN_SITU, Some("Pengukuran in-situ tanpa kolom metode"))
+    } else {
+        sumber(field, nomor, text, kata_kunci, KEYAKINAN_TINGGI, None)
+    };
+
+    let satuan_asal = nilai.satuan.as_deref().unwrap_or_default();
+    match konversi {
+        Konversi::Baku(_) => {}
+        Konversi::Dikonversi { nilai: baku, faktor } => {
+            println!("   ↳ {} {} {} -> {} (x{})", field, nilai.nilai, satuan_asal, baku, faktor);
+            asal.catatan.get_or_insert_with(|| format!(
+                "Dikonversi dari {} ke {}", satuan_asal, satuan_parameter(field).unwrap_or("-")
+            ));
+        }
+        Konversi::TidakDikenal(_) => {
+            let pesan = satuan::pesan_tidak_dikenal(field, satuan_asal);
+            println!("⚠️ {}", pesan);
+            asal.keyakinan = asal.keyakinan.min(KEYAKINAN_RENDAH);
+            asal.catatan = Some(pesan);
+        }
+    }
+    asal.satuan = nilai.satuan;
+    catat(laporan, asal);
+    true
+}
+
+/// Parse banyak PDF sekaligus. Setiap file diproses paralel di thread pool blocking
+/// (ekstraksi teks PDF bersifat CPU-bound), hasil dikembalikan sesuai urutan input.
+/// PDF multi-sampel menghasilkan satu item per kolom sampel.
+/// Template lab dideteksi otomatis per file.
+pub async fn parse_pdf_batch(paths: Vec<String>, templates: Vec<TemplateLab>) -> Vec<HasilImportPdf> {
+    let templates = Arc::new(templates);
+    let tasks: Vec<_> = paths.into_iter()
+        .map(|path| {
+            let handle = tauri::async_runtime::spawn_blocking({
+                let path = path.clone();
+                let templates = Arc::clone(&templates);
+                move || parse_pdf(path, &templates)
+            });
+            (path, handle)
+        })
+        .collect();
+
+    let mut hasil = Vec::with_capacity(tasks.len());
+    for (file, handle) in tasks {
+        let parsed = handle.await
+            .map_err(|e| format!("Proses ekstraksi terhenti: {}", e))
+            .and_then(|r| r);
+
+        match parsed {
+            Ok(sampel) => {
+                let multi = sampel.len() > 1;
+                for (ke, HasilEkstraksi { record, laporan }) in sampel.into_iter().enumerate() {
+                    hasil.push(HasilImportPdf {
+                        file: file.clone(),
+                        sampel_ke: multi.then_some(ke + 1),
+                        peringatan: cek_kelengkapan(&record),
+                        record: Some(record),
+                        laporan: Some(laporan),
+                        error: None,
+                    });
                 }
             }
-        },
-        None => {}
+            Err(e) => hasil.push(HasilImportPdf { file, sampel_ke: None, record: None, laporan: None, peringatan: Vec::new(), error: Some(e) }),
+        }
+    }
+
+    hasil
+}
+
+/// Daftar file PDF di dalam satu folder (tidak rekursif), terurut nama file
+pub fn list_pdf_folder(folder: &str) -> Result<Vec<String>, String> {
+    let entries = fs::read_dir(folder)
+        .map_err(|e| format!("Gagal membaca folder {}: {}", folder, e))?;
+
+    let mut paths: Vec<String> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && is_pdf(path))
+        .filter_map(|path| path.to_str().map(str::to_string))
+        .collect();
+    paths.sort();
+
+    Ok(paths)
+}
+
+fn is_pdf(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("pdf"))
+}
+
+/// Peringatan untuk field penting yang tidak berhasil diekstrak
+pub fn cek_kelengkapan(data: &KualitasAirRecord) -> Vec<String> {
+    let mut peringatan = Vec::new();
+
+    if data.nama_pos.is_none() {
+        peringatan.push("Nama pos tidak ditemukan".to_string());
+    }
+    if data.tanggal_sampling.is_none() {
+        peringatan.push("Tanggal sampling tidak ditemukan".to_string());
+    }
+    if data.koordinat_geografis.is_none() {
+        peringatan.push("Koordinat tidak ditemukan".to_string());
+    }
+
+    let jumlah_parameter = NAMA_PARAMETER.iter()
+        .filter(|p| data.nilai_parameter(p).is_some())
+        .count();
+    if jumlah_parameter == 0 {
+        peringatan.push("Tidak ada parameter yang berhasil diekstrak".to_string());
+    }
+
+    peringatan
+}
+
+// Nilai satu baris hasil uji
+struct NilaiBaris {
+    nilai: f64,
+    kualifier: Option<Kualifier>, // Some untuk nilai "<" / ">" (nilai = batas deteksi)
+    satuan: Option<String>,
+}
+
+// Kualifier "<" / ">" yang menempel pada token nilai atau berdiri sebagai token sebelumnya
+fn kualifier_token(token: &str, sebelum: Option<&str>) -> Option<Kualifier> {
+    if token.contains('<') || sebelum == Some("<") {
+        Some(Kualifier::KurangDari)
+    } else if token.contains('>') || sebelum == Some(">") {
+        Some(Kualifier::LebihDari)
+    } else {
+        None
+    }
+}
+
+// Angka dari satu token ("7,2" / "<0,002"); token bersatuan / berhuruf dilewati
+fn angka_token(token: &str) -> Option<f64> {
+    if token.chars().any(char::is_alphabetic) { return None; }
+    token.trim_matches(|c: char| c == '(' || c == ')')
+        .replace(',', ".")
+        .replace(['<', '>'], "")
+        .parse::<f64>()
+        .ok()
+}
+
+// Ambil angka setelah kata kunci pada baris ukur lapangan (tanpa kolom metode),
+// sebanyak jumlah kolom sampel. Token satuan seperti "mg/L", "(°C)", "NTU" dilewati karena mengandung huruf.
+fn extract_in_situ_value(setelah_kata: &str, param_name: &str, jumlah: usize) -> Vec<NilaiBaris> {
+    let parts: Vec<&str> = setelah_kata.split_whitespace().collect();
+    let posisi: Vec<usize> = (0..parts.len())
+        .filter(|&i| angka_token(parts[i]).is_some())
+        .take(jumlah)
+        .collect();
+    if posisi.len() < jumlah { return Vec::new(); }
+
+    let satuan = satuan::deteksi(param_name, &tanpa_indeks(&parts, &posisi));
+    posisi.iter()
+        .filter_map(|&i| nilai_token(&parts, i, satuan.clone()))
+        .inspect(|n| println!("[OK] {} (in-situ): {}", param_name, n.nilai))
+        .collect()
+}
+
+// Nilai dari token ke-idx beserta kualifier yang menempel ("<0,002") atau terpisah satu token ("< 0,002")
+fn nilai_token(parts: &[&str], idx: usize, satuan: Option<String>) -> Option<NilaiBaris> {
+    let nilai = angka_token(parts[idx])?;
+    let sebelum = idx.checked_sub(1).map(|i| parts[i]);
+    let kualifier = kualifier_token(parts[idx], sebelum);
+    Some(NilaiBaris { nilai, kualifier, satuan })
+}
+
+// Token baris selain token nilai (kandidat kolom satuan)
+fn tanpa_indeks<'a>(parts: &[&'a str], nilai: &[usize]) -> Vec<&'a str> {
+    parts.iter()
+        .enumerate()
+        .filter(|(i, _)| !nilai.contains(i))
+        .map(|(_, t)| *t)
+        .collect()
+}
+
+// Posisi byte kemunculan pertama `pola` di `teks`, huruf ASCII tidak peka besar/kecil.
+// Dicari langsung di teks asli: to_uppercase() bisa mengubah panjang byte (ligatur "ﬁ" -> "FI").
+fn cari_tanpa_kapital(teks: &str, pola: &str) -> Option<usize> {
+    let pola = pola.as_bytes();
+    teks.char_indices()
+        .map(|(i, _)| i)
+        .find(|&i| teks.as_bytes()[i..].get(..pola.len()).is_some_and(|b| b.eq_ignore_ascii_case(pola)))
+}
+
+// Posisi byte penanda metode pertama pada baris (tidak peka huruf besar/kecil)
+fn posisi_metode(raw_line: &str, template: &TemplateLab) -> Option<usize> {
+    template.penanda_metode.iter()
+        .filter(|p| !p.trim().is_empty())
+        .filter_map(|p| cari_tanpa_kapital(raw_line, p))
+        .min()
+}
+
+// Ambil nilai dari kolom sebelum penanda metode (posisi sesuai template).
+// Laporan multi-sampel: kolom nilai berderet ke kiri dari posisi tersebut, satu per sampel
+// (dikembalikan urut kiri ke kanan). Vec kosong jika jumlah nilai tidak sesuai.
+// Nilai "<" / ">" dikembalikan apa adanya (batas deteksi) beserta kualifiernya.
+fn extract_sni_value(raw_line: &str, param_name: &str, template: &TemplateLab, jumlah: usize) -> Vec<NilaiBaris> {
+    let Some(left_part) = posisi_metode(raw_line, template).and_then(|idx| raw_line.get(..idx)) else { return Vec::new() };
+    let parts: Vec<&str> = left_part.split_whitespace().collect();
+    let Some(idx_nilai) = parts.len().checked_sub(template.posisi_nilai) else { return Vec::new() };
+
+    // Kumpulkan token nilai mundur dari kolom nilai terakhir; "<" / ">" terpisah ikut nilai di kanannya
+    let mut posisi: Vec<usize> = Vec::new();
+    for idx in (0..=idx_nilai).rev() {
+        if posisi.len() == jumlah { break; }
+        match parts[idx] {
+            "<" | ">" if !posisi.is_empty() => continue,
+            token if angka_token(token).is_some() => posisi.push(idx),
+            _ => break,
+        }
+    }
+    if posisi.len() < jumlah { return Vec::new(); }
+    posisi.reverse();
+
+    // Kolom satuan dari template; jika tidak ada, cari token satuan di baris
+    let satuan = match template.posisi_satuan {
+        Some(posisi_satuan) => parts.len().checked_sub(posisi_satuan).map(|i| parts[i].to_string()),
+        None => satuan::deteksi(param_name, &tanpa_indeks(&parts, &posisi)),
+    };
+
+    posisi.iter()
+        .filter_map(|&i| nilai_token(&parts, i, satuan.clone()))
+        .inspect(|n| println!("[OK] {}: {}{}", param_name, match n.kualifier {
+            Some(Kualifier::KurangDari) => "<",
+            Some(Kualifier::LebihDari) => ">",
+            None => "",
+        }, n.nilai))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::template_lab::template_default;
+
+    #[test]
+    fn posisi_metode_pada_baris_berligatur() {
+        let template = template_default();
+
+        // "ﬁ" (3 byte) menjadi "FI" (2 byte) dan "ŉ" (2 byte) menjadi "ʼN" (3 byte) saat di-uppercase
+        for baris in ["Koeﬁsien ﬁltrasi ﬁ ﬁ    mg/L   36   SNI 06-6989.3-2004", "ŉŉŉ Besi (Fe)   mg/L   310   sni 6989.4:2009"] {
+            let posisi = posisi_metode(baris, &template).expect("penanda metode tidak ditemukan");
+            assert!(baris[posisi..].to_ascii_uppercase().starts_with("SNI"), "{}", baris);
+        }
+        assert_eq!(posisi_metode("Debit   L/detik   850   in situ", &template), Some(24));
+
+        let nilai = extract_sni_value("Zat Padat Tersuspensi ﬁ ﬁ ﬁ ﬁ ﬁ ﬁ ﬁ ﬁ (TSS)  mg/L  36  SNI 06-6989.3-2004", "tss", &template, 1);
+        assert_eq!(nilai.iter().map(|n| n.nilai).collect::<Vec<_>>(), [36.0]);
     }
-    None
-}
\ No newline at end of file
+}
@@ -0,0 +1,272 @@
+use crate::models::import_pdf::{LaporanEkstraksi, SumberField};
+use crate::models::kualitas_air::KualitasAirRecord;
+use crate::models::pos::Pos;
+use sqlx::{Sqlite, SqliteConnection, SqlitePool, Transaction};
+
+// Keyakinan nilai yang diambil dari data tersimpan (bukan dari teks PDF)
+const KEYAKINAN_REGISTRY: f64 = 0.8;
+
+// Nama pos & koordinat (sudah di-trim) sebagai kunci pencarian pos; None jika nama pos kosong
+fn kunci_pos(data: &KualitasAirRecord) -> Option<(&str, Option<&str>)> {
+    let nama_pos = data.nama_pos.as_deref().map(str::trim).filter(|n| !n.is_empty())?;
+    let koordinat = data.koordinat_geografis.as_deref().map(str::trim).filter(|k| !k.is_empty());
+    Some((nama_pos, koordinat))
+}
+
+/// Cari pos tersimpan untuk sebuah sampel tanpa mengubah database:
+/// 1. pos_id yang sudah diisi, selama nama & koordinat di record masih sama dengan pos tersebut
+/// 2. Pos dengan nama & koordinat yang sama (misal nama pos diubah di form edit)
+///
+/// Record tanpa nama_pos memakai pos_id apa adanya.
+/// Dipakai sebelum sampel disimpan, misalnya untuk memuat suhu alamiah pos.
+pub async fn cari_pos(
+    conn: &mut SqliteConnection,
+    data: &KualitasAirRecord
+) -> Result<Option<i64>, String> {
+    let Some((nama_pos, koordinat)) = kunci_pos(data) else { return Ok(data.pos_id) };
+
+    if let Some(id) = data.pos_id {
+        let tersimpan: Option<(String, Option<String>)> = sqlx::query_as("SELECT nama_pos, koordinat_geografis FROM pos WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| format!("Gagal mencari pos: {}", e))?;
+
+        let sama = tersimpan.is_some_and(|(nama, koordinat_tersimpan)| {
+            nama.trim() == nama_pos
+                && koordinat_tersimpan.as_deref().map(str::trim).filter(|k| !k.is_empty()) == koordinat
+        });
+        if sama {
+            return Ok(Some(id));
+        }
+    }
+
+    sqlx::query_scalar("SELECT id FROM pos WHERE nama_pos = ? AND koordinat_geografis IS ?")
+        .bind(nama_pos)
+        .bind(koordinat)
+        .fetch_optional(conn)
+        .await
+        .map_err(|e| format!("Gagal mencari pos: {}", e))
+}
+
+// Sampel yang sudah tercatat di pos ini (edit data): isian record menimpa metadata pos
+const SQL_TIMPA_POS: &str = "
+    UPDATE pos SET
+        das = COALESCE($1, das), wilayah_sungai = COALESCE($2, wilayah_sungai),
+        provinsi = COALESCE($3, provinsi), kabupaten = COALESCE($4, kabupaten),
+        kecamatan = COALESCE($5, kecamatan), desa = COALESCE($6, desa),
+        sungai = COALESCE($7, sungai), elevasi_pos = COALESCE($8, elevasi_pos),
+        kelas_baku_mutu = COALESCE($9, kelas_baku_mutu)
+    WHERE id = $10
+";
+
+// Sampel baru di pos yang sudah terdaftar: hanya kolom registry yang masih kosong yang dilengkapi
+const SQL_LENGKAPI_POS: &str = "
+    UPDATE pos SET
+        das = COALESCE(das, $1), wilayah_sungai = COALESCE(wilayah_sungai, $2),
+        provinsi = COALESCE(provinsi, $3), kabupaten = COALESCE(kabupaten, $4),
+        kecamatan = COALESCE(kecamatan, $5), desa = COALESCE(desa, $6),
+        sungai = COALESCE(sungai, $7), elevasi_pos = COALESCE(elevasi_pos, $8),
+        kelas_baku_mutu = COALESCE(kelas_baku_mutu, $9)
+    WHERE id = $10
+";
+
+/// Tentukan pos_id untuk sebuah sampel sebelum disimpan:
+/// 1. pos_id yang sudah diisi di record (metadata pos diperbarui dari record)
+/// 2. Pos dengan nama & koordinat yang sama (kolom kosong di registry dilengkapi dari record)
+/// 3. Pos baru dari metadata header record
+///
+/// Jika nama atau koordinat pos diubah, sampel dipindah ke pos yang cocok (langkah 2 / 3).
+/// Record tanpa pos_id dan tanpa nama_pos tidak dikaitkan ke pos mana pun.
+pub async fn resolve_pos(
+    tx: &mut Transaction<'_, Sqlite>,
+    data: &KualitasAirRecord
+) -> Result<Option<i64>, String> {
+    let Some((nama_pos, koordinat)) = kunci_pos(data) else { return Ok(data.pos_id) };
+
+    if let Some(id) = cari_pos(tx, data).await? {
+        let sql = if data.pos_id == Some(id) { SQL_TIMPA_POS } else { SQL_LENGKAPI_POS };
+        sqlx::query(sql)
+            .bind(&data.das)
+            .bind(&data.wilayah_sungai)
+            .bind(&data.provinsi)
+            .bind(&data.kabupaten)
+            .bind(&data.kecamatan)
+            .bind(&data.desa)
+            .bind(&data.sungai)
+            .bind(&data.elevasi_pos)
+            .bind(data.kelas_baku_mutu)
+            .bind(id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| format!("Gagal memperbarui pos: {}", e))?;
+        return Ok(Some(id));
+    }
+
+    let sql = "
+        INSERT INTO pos (
+            nama_pos, das, wilayah_sungai, provinsi, kabupaten, kecamatan, desa, sungai,
+            elevasi_pos, koordinat_geografis, kelas_baku_mutu
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+    ";
+    let id = sqlx::query(sql)
+        .bind(nama_pos)
+        .bind(&data.das)
+        .bind(&data.wilayah_sungai)
+        .bind(&data.provinsi)
+        .bind(&data.kabupaten)
+        .bind(&data.kecamatan)
+        .bind(&data.desa)
+        .bind(&data.sungai)
+        .bind(&data.elevasi_pos)
+        .bind(koordinat)
+        .bind(data.kelas_baku_mutu)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| format!("Gagal membuat pos baru: {}", e))?
+        .last_insert_rowid();
+
+    println!("🦀 [RUST] Pos baru terdaftar: {} (ID: {})", nama_pos, id);
+    Ok(Some(id))
+}
+
+/// Lengkapi metadata hasil ekstraksi PDF dari data tersimpan jika nama pos sudah dikenal:
+/// metadata pos dari registry, laboratorium & pelaksana dari sampel terakhir pos tersebut.
+/// Hanya field yang masih kosong yang diisi; setiap isian dicatat di laporan ekstraksi.
+pub async fn lengkapi_dari_registry(
+    pool: &SqlitePool,
+    data: &mut KualitasAirRecord,
+    laporan: &mut LaporanEkstraksi
+) -> Result<(), String> {
+    let Some(nama_pos) = data.nama_pos.as_deref().map(str::trim).filter(|n| !n.is_empty()) else {
+        return Ok(());
+    };
+
+    // Jika ada beberapa pos dengan nama sama, utamakan yang koordinatnya cocok, lalu yang terbaru
+    let pos = sqlx::query_as::<_, Pos>("
+        SELECT * FROM pos
+        WHERE nama_pos = ? COLLATE NOCASE
+        ORDER BY (koordinat_geografis IS ?) DESC, id DESC
+        LIMIT 1
+    ")
+        .bind(nama_pos)
+        .bind(data.koordinat_geografis.as_deref().map(str::trim))
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Gagal mencari pos: {}", e))?;
+
+    let Some(pos) = pos else { return Ok(()) };
+    let pos_id = pos.id;
+
+    let terakhir: Option<(Option<String>, Option<String>)> = sqlx::query_as(
+        "SELECT laboratorium, pelaksana FROM kualitas_air WHERE pos_id = ? ORDER BY id DESC LIMIT 1"
+    )
+        .bind(pos_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Gagal mengambil sampel terakhir pos: {}", e))?;
+    let (laboratorium, pelaksana) = terakhir.unwrap_or_default();
+
+    let tersimpan = [
+        ("das", pos.das),
+        ("wilayah_sungai", pos.wilayah_sungai),
+        ("provinsi", pos.provinsi),
+        ("kabupaten", pos.kabupaten),
+        ("kecamatan", pos.kecamatan),
+        ("desa", pos.desa),
+        ("sungai", pos.sungai),
+        ("elevasi_pos", pos.elevasi_pos),
+        ("koordinat_geografis", pos.koordinat_geografis),
+        ("laboratorium", laboratorium),
+        ("pelaksana", pelaksana),
+    ];
+
+    for (field, nilai) in tersimpan {
+        let Some(nilai) = nilai else { continue };
+        let Some(slot) = data.metadata_mut(field) else { continue };
+        if slot.is_some() { continue; }
+
+        laporan.field.push(SumberField {
+            field: field.to_string(),
+            nomor_baris: 0,
+            baris: nilai.clone(),
+            kata_kunci: format!("pos: {}", pos.nama_pos),
+            satuan: None,
+            keyakinan: KEYAKINAN_REGISTRY,
+            catatan: Some("Diambil dari data pos tersimpan".to_string()),
+        });
+        *slot = Some(nilai);
+    }
+
+    if data.kelas_baku_mutu.is_none() {
+        data.kelas_baku_mutu = pos.kelas_baku_mutu;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::db_service;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn pool_uji() -> SqlitePool {
+        // Satu koneksi: setiap koneksi ":memory:" punya database sendiri
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        db_service::run_migrations(&pool, &std::env::temp_dir()).await.unwrap();
+        pool
+    }
+
+    async fn resolve(pool: &SqlitePool, data: &KualitasAirRecord) -> Option<i64> {
+        let mut tx = pool.begin().await.unwrap();
+        let id = resolve_pos(&mut tx, data).await.unwrap();
+        tx.commit().await.unwrap();
+        id
+    }
+
+    async fn pos(pool: &SqlitePool, id: i64) -> Pos {
+        sqlx::query_as("SELECT * FROM pos WHERE id = ?").bind(id).fetch_one(pool).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn edit_metadata_pos_sampel_tersimpan() {
+        let pool = pool_uji().await;
+        let baru = KualitasAirRecord {
+            nama_pos: Some("Pos Hulu".to_string()),
+            koordinat_geografis: Some("-3.95, 122.51".to_string()),
+            das: Some("DAS Konaweha".to_string()),
+            ..Default::default()
+        };
+        let hulu = resolve(&pool, &baru).await.unwrap();
+
+        // Edit DAS & kabupaten (nama & koordinat sama): pos yang sama diperbarui
+        let edit = KualitasAirRecord {
+            pos_id: Some(hulu),
+            das: Some("DAS Lasolo".to_string()),
+            kabupaten: Some("Konawe".to_string()),
+            ..baru.clone()
+        };
+        assert_eq!(resolve(&pool, &edit).await, Some(hulu));
+        let tersimpan = pos(&pool, hulu).await;
+        assert_eq!(tersimpan.das.as_deref(), Some("DAS Lasolo"));
+        assert_eq!(tersimpan.kabupaten.as_deref(), Some("Konawe"));
+
+        // Sampel baru tanpa pos_id hanya melengkapi kolom kosong, tidak menimpa
+        let sampel_lain = KualitasAirRecord { das: Some("DAS Lain".to_string()), ..baru.clone() };
+        assert_eq!(resolve(&pool, &sampel_lain).await, Some(hulu));
+        assert_eq!(pos(&pool, hulu).await.das.as_deref(), Some("DAS Lasolo"));
+
+        // Ganti nama pos: sampel dipindah ke pos baru, pos lama tidak berubah
+        let ganti_nama = KualitasAirRecord { nama_pos: Some("Pos Hilir".to_string()), ..edit.clone() };
+        let hilir = resolve(&pool, &ganti_nama).await.unwrap();
+        assert_ne!(hilir, hulu);
+        assert_eq!(pos(&pool, hilir).await.nama_pos, "Pos Hilir");
+        assert_eq!(pos(&pool, hulu).await.nama_pos, "Pos Hulu");
+
+        // Nama dikembalikan: sampel kembali ke pos lama
+        let kembali = KualitasAirRecord { pos_id: Some(hilir), ..edit.clone() };
+        let mut conn = pool.acquire().await.unwrap();
+        assert_eq!(cari_pos(&mut conn, &kembali).await.unwrap(), Some(hulu));
+    }
+}
@@ -0,0 +1,129 @@
+use crate::models::kualitas_air::{KualitasAirRecord, NAMA_PARAMETER};
+use crate::models::query_kualitas_air::{
+    ArahUrutan, FilterKualitasAir, HalamanKualitasAir, JumlahStatus, QueryKualitasAir,
+};
+use crate::services::nilai_tersensor;
+use sqlx::sqlite::SqliteArguments;
+use sqlx::{Arguments, SqlitePool};
+
+// Batas data per halaman agar webview tidak menerima ribuan baris sekaligus
+const PER_HALAMAN_MAKS: u32 = 500;
+
+// Klausa WHERE bersama untuk data, jumlah total, dan ringkasan status.
+// Urutan $1..$9 HARUS cocok dengan argumen_filter.
+const SQL_FILTER: &str = "
+    WHERE ($1 IS NULL OR pos_id = $1)
+      AND ($2 IS NULL OR nama_pos = $2 COLLATE NOCASE)
+      AND ($3 IS NULL OR das = $3 COLLATE NOCASE)
+      AND ($4 IS NULL OR kabupaten = $4 COLLATE NOCASE)
+      AND ($5 IS NULL OR tanggal_sampling >= $5)
+      AND ($6 IS NULL OR tanggal_sampling <= $6)
+      AND ($7 IS NULL OR status_ip = $7)
+      AND ($8 IS NULL OR status_storet LIKE 'Kelas ' || $8 || '%')
+      AND ($9 IS NULL
+           OR nama_pos LIKE $9 ESCAPE '\\' OR das LIKE $9 ESCAPE '\\' OR sungai LIKE $9 ESCAPE '\\'
+           OR wilayah_sungai LIKE $9 ESCAPE '\\' OR kabupaten LIKE $9 ESCAPE '\\'
+           OR laboratorium LIKE $9 ESCAPE '\\' OR pelaksana LIKE $9 ESCAPE '\\')
+";
+
+// Teks filter kosong diperlakukan sama dengan tidak diisi
+fn teks(nilai: &Option<String>) -> Option<String> {
+    nilai.as_deref().map(str::trim).filter(|s| !s.is_empty()).map(str::to_string)
+}
+
+// Pola LIKE untuk pencarian teks bebas (% dan _ dari pengguna dianggap huruf biasa)
+fn pola_cari(cari: &str) -> String {
+    let aman = cari.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    format!("%{}%", aman)
+}
+
+// Argumen $1..$9 sesuai SQL_FILTER
+fn argumen_filter(filter: &FilterKualitasAir) -> SqliteArguments<'static> {
+    let mut args = SqliteArguments::default();
+    args.add(filter.pos_id);
+    args.add(teks(&filter.nama_pos));
+    args.add(teks(&filter.das));
+    args.add(teks(&filter.kabupaten));
+    args.add(filter.tanggal_awal);
+    args.add(filter.tanggal_akhir);
+    args.add(teks(&filter.status_ip));
+    args.add(teks(&filter.kelas_storet).map(|k| k.to_uppercase()));
+    args.add(teks(&filter.cari).map(|c| pola_cari(&c)));
+    args
+}
+
+/// Klausa ORDER BY dari nama kolom (hanya kolom yang dikenal, mencegah SQL injection).
+/// Nilai kosong selalu di akhir; id sebagai penentu urutan yang stabil.
+pub fn klausa_urutan(urut: Option<&str>, arah: ArahUrutan) -> Result<String, String> {
+    let arah = match arah {
+        ArahUrutan::Asc => "ASC",
+        ArahUrutan::Desc => "DESC",
+    };
+    let kolom = urut.map(str::trim).filter(|s| !s.is_empty()).unwrap_or("tanggal_sampling");
+
+    let dikenal = [
+        "id", "nama_pos", "das", "kabupaten", "sungai", "nilai_ip", "status_ip",
+        "nilai_storet", "status_storet", "created_at",
+    ];
+    match kolom {
+        "tanggal_sampling" => Ok(format!(
+            "ORDER BY tanggal_sampling {arah} NULLS LAST, waktu_sampling {arah} NULLS LAST, id {arah}"
+        )),
+        k if dikenal.contains(&k) || NAMA_PARAMETER.contains(&k) => Ok(format!("ORDER BY {k} {arah} NULLS LAST, id {arah}")),
+        k => Err(format!("Kolom urutan tidak dikenal: {}", k)),
+    }
+}
+
+/// Ambil satu halaman data sesuai filter, beserta jumlah total & ringkasan status IP
+pub async fn cari(pool: &SqlitePool, query: &QueryKualitasAir) -> Result<HalamanKualitasAir, String> {
+    let halaman = query.halaman.max(1);
+    let per_halaman = query.per_halaman.clamp(1, PER_HALAMAN_MAKS);
+    let urutan = klausa_urutan(query.urut.as_deref(), query.arah)?;
+
+    // 1. Total data yang lolos filter
+    let sql_total = format!("SELECT COUNT(*) FROM v_kualitas_air {}", SQL_FILTER);
+    let total: i64 = sqlx::query_scalar_with(&sql_total, argumen_filter(&query.filter))
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Gagal menghitung data: {}", e))?;
+
+    // 2. Ringkasan status IP (untuk kartu statistik)
+    let sql_status = format!(
+        "SELECT status_ip AS status, COUNT(*) AS jumlah FROM v_kualitas_air {} GROUP BY status_ip ORDER BY status_ip",
+        SQL_FILTER
+    );
+    let per_status_ip: Vec<JumlahStatus> = sqlx::query_as_with(&sql_status, argumen_filter(&query.filter))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Gagal meringkas status IP: {}", e))?;
+
+    // 3. Data halaman ini
+    let sql_data = format!("SELECT * FROM v_kualitas_air {} {} LIMIT $10 OFFSET $11", SQL_FILTER, urutan);
+    let mut args = argumen_filter(&query.filter);
+    args.add(i64::from(per_halaman));
+    args.add(i64::from(halaman - 1) * i64::from(per_halaman));
+
+    let mut data: Vec<KualitasAirRecord> = sqlx::query_as_with(&sql_data, args)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Gagal mengambil data: {}", e))?;
+    nilai_tersensor::lengkapi(pool, &mut data).await?;
+
+    Ok(HalamanKualitasAir { data, total, halaman, per_halaman, per_status_ip })
+}
+
+/// Ambil seluruh data yang lolos filter (tanpa paginasi), misal untuk export
+pub async fn semua(
+    pool: &SqlitePool,
+    filter: &FilterKualitasAir,
+    urut: Option<&str>,
+    arah: ArahUrutan
+) -> Result<Vec<KualitasAirRecord>, String> {
+    let sql = format!("SELECT * FROM v_kualitas_air {} {}", SQL_FILTER, klausa_urutan(urut, arah)?);
+    let mut data: Vec<KualitasAirRecord> = sqlx::query_as_with(&sql, argumen_filter(filter))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Gagal mengambil data: {}", e))?;
+    nilai_tersensor::lengkapi(pool, &mut data).await?;
+    Ok(data)
+}
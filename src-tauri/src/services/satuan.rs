@@ -0,0 +1,103 @@
+use crate::models::kualitas_air::satuan_parameter;
+
+// Satuan alternatif per kelompok parameter: (bentuk ternormalisasi, faktor ke satuan baku)
+// Bentuk ternormalisasi: huruf kecil, tanpa spasi/kurung/°/"-", µ -> u, ³ -> 3
+const MASSA_PER_VOLUME: &[(&str, f64)] = &[
+    ("mg/l", 1.0), ("ppm", 1.0), ("mg/dm3", 1.0),
+    ("ug/l", 0.001), ("ppb", 0.001),
+    ("ng/l", 0.000001),
+    ("g/l", 1000.0),
+];
+const TEMPERATUR: &[(&str, f64)] = &[("c", 1.0), ("oc", 1.0), ("celcius", 1.0), ("celsius", 1.0)];
+const KONDUKTIVITAS: &[(&str, f64)] = &[
+    ("us/cm", 1.0), ("umhos/cm", 1.0), ("umho/cm", 1.0),
+    ("ms/cm", 1000.0), ("ms/m", 10.0),
+];
+const KEKERUHAN: &[(&str, f64)] = &[("ntu", 1.0), ("fnu", 1.0), ("ftu", 1.0)];
+const WARNA: &[(&str, f64)] = &[("ptco", 1.0), ("tcu", 1.0), ("skalaptco", 1.0), ("unitptco", 1.0)];
+// "MPN/100 mL" sering terpecah jadi dua token, bentuk "/100" saja ikut dikenali
+const KOLIFORM: &[(&str, f64)] = &[
+    ("mpn/100ml", 1.0), ("jml/100ml", 1.0), ("jumlah/100ml", 1.0), ("cfu/100ml", 1.0), ("koloni/100ml", 1.0),
+    ("mpn/100", 1.0), ("jml/100", 1.0), ("jumlah/100", 1.0), ("cfu/100", 1.0), ("koloni/100", 1.0),
+];
+const DEBIT: &[(&str, f64)] = &[
+    ("m3/detik", 1.0), ("m3/dtk", 1.0), ("m3/s", 1.0),
+    ("l/detik", 0.001), ("l/dtk", 0.001), ("l/s", 0.001), ("liter/detik", 0.001),
+];
+
+/// Hasil penyesuaian satu nilai ke satuan baku
+pub enum Konversi {
+    Baku(f64),                              // Sudah dalam satuan baku (atau satuan tidak tercantum)
+    Dikonversi { nilai: f64, faktor: f64 }, // Dikalikan faktor dari satuan laporan
+    TidakDikenal(f64),                      // Satuan asing: nilai dibiarkan apa adanya
+}
+
+fn normalisasi(token: &str) -> String {
+    token.to_lowercase()
+        .replace(['µ', 'μ'], "u")
+        .replace('³', "3")
+        .chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '(' | ')' | '[' | ']' | '°' | 'º' | '-' | '.'))
+        .collect()
+}
+
+fn tabel(parameter: &str) -> &'static [(&'static str, f64)] {
+    match parameter {
+        "temperatur" => TEMPERATUR,
+        "konduktivitas" => KONDUKTIVITAS,
+        "kekeruhan" => KEKERUHAN,
+        "warna" => WARNA,
+        "total_coliform" => KOLIFORM,
+        "debit" => DEBIT,
+        "ph" => &[],
+        _ => MASSA_PER_VOLUME,
+    }
+}
+
+// Faktor konversi satuan untuk parameter (None jika satuan tidak dikenal untuk parameter ini)
+fn faktor(parameter: &str, satuan: &str) -> Option<f64> {
+    let kunci = normalisasi(satuan);
+    tabel(parameter).iter().find(|(s, _)| *s == kunci).map(|(_, f)| *f)
+}
+
+// Token yang tampak seperti satuan: mengandung "/" atau dikenal di kelompok parameter mana pun
+fn mirip_satuan(token: &str) -> bool {
+    let kunci = normalisasi(token);
+    if kunci.is_empty() || kunci.starts_with(|c: char| c.is_ascii_digit()) { return false; }
+    kunci.contains('/')
+        || [MASSA_PER_VOLUME, TEMPERATUR, KONDUKTIVITAS, KEKERUHAN, WARNA, KOLIFORM, DEBIT]
+            .iter()
+            .any(|t| t.iter().any(|(s, _)| *s == kunci))
+}
+
+/// Cari token satuan di antara potongan baris laporan.
+/// Satuan yang dikenal untuk parameter didahulukan; jika tidak ada, token pertama
+/// yang tampak seperti satuan dikembalikan (akan ditandai tidak dikenal saat konversi).
+pub fn deteksi(parameter: &str, tokens: &[&str]) -> Option<String> {
+    if tabel(parameter).is_empty() { return None; } // Parameter tanpa satuan (pH)
+
+    tokens.iter()
+        .find(|t| faktor(parameter, t).is_some())
+        .or_else(|| tokens.iter().find(|t| mirip_satuan(t)))
+        .map(|t| t.trim_matches(|c| c == '(' || c == ')').to_string())
+}
+
+/// Sesuaikan nilai laporan ke satuan baku parameter
+pub fn ke_satuan_baku(parameter: &str, nilai: f64, satuan: Option<&str>) -> Konversi {
+    let Some(satuan) = satuan.filter(|s| !normalisasi(s).is_empty()) else { return Konversi::Baku(nilai) };
+
+    match faktor(parameter, satuan) {
+        Some(1.0) => Konversi::Baku(nilai),
+        // Dibulatkan agar 5 µg/L menjadi 0.005, bukan 0.005000000000000001
+        Some(f) => Konversi::Dikonversi { nilai: (nilai * f * 1e9).round() / 1e9, faktor: f },
+        None => Konversi::TidakDikenal(nilai),
+    }
+}
+
+/// Pesan peringatan untuk satuan yang tidak dikenal
+pub fn pesan_tidak_dikenal(parameter: &str, satuan: &str) -> String {
+    format!(
+        "Satuan '{}' tidak dikenal untuk {} (satuan baku {}), nilai tidak dikonversi",
+        satuan, parameter, satuan_parameter(parameter).unwrap_or("-")
+    )
+}
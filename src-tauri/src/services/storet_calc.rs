@@ -1,44 +1,87 @@
+use crate::models::baku_mutu::{BatasParameter, LimitType};
+use crate::models::hasil_perhitungan::{Kategori, RincianParameterStoret};
 use crate::models::kualitas_air::KualitasAirRecord;
+use crate::models::nilai_tersensor::KebijakanSensor;
+use crate::models::suhu_alamiah::SuhuAlamiah;
+use crate::services::suhu_alamiah;
+
+// Denda STORET untuk satu parameter: (Maksimum, Minimum, Rata-rata)
+struct SkorStoret {
+    maks: f64,
+    min: f64,
+    rata: f64,
+}
 
-// Kategori Parameter
-enum Kategori {
-    Fisika,
-    Kimia,
-    Biologi,
+impl SkorStoret {
+    // Tabel Skor Kepmen LH 115/2003
+    // Jumlah sampel < 10 : Fisika -1/-1/-3, Kimia -2/-2/-4, Biologi -3/-3/-5
+    // Jumlah sampel >= 10: Fisika -2/-2/-6, Kimia -4/-4/-8, Biologi -6/-6/-10
+    fn untuk(kat: &Kategori, jumlah_sampel: usize) -> SkorStoret {
+        let (maks_min, rata) = match (kat, jumlah_sampel >= 10) {
+            (Kategori::Fisika, false) => (-1.0, -3.0),
+            (Kategori::Kimia, false) => (-2.0, -4.0),
+            (Kategori::Biologi, false) => (-3.0, -5.0),
+            (Kategori::Fisika, true) => (-2.0, -6.0),
+            (Kategori::Kimia, true) => (-4.0, -8.0),
+            (Kategori::Biologi, true) => (-6.0, -10.0),
+        };
+        SkorStoret { maks: maks_min, min: maks_min, rata }
+    }
 }
 
-// Jenis Limit yang dicek
-enum LimitType {
-    Max,     // Data tidak boleh > Baku
-    Min,     // Data tidak boleh < Baku (DO)
-    Range,   // Data harus di dalam range (pH)
+// Kelompok parameter untuk tabel skor STORET
+fn kategori(parameter: &str) -> Kategori {
+    match parameter {
+        "temperatur" | "konduktivitas" | "kekeruhan" | "tds" | "tss" | "warna" | "debit" => Kategori::Fisika,
+        "total_coliform" => Kategori::Biologi,
+        _ => Kategori::Kimia,
+    }
 }
 
 /// Fungsi Utama Menghitung Skor STORET (Multi-Sample)
 /// Menerima Vector data, bukan single struct.
-pub fn calculate_storet_collection(dataset: &Vec<KualitasAirRecord>) -> (f64, String) {
+/// Baku mutu diambil dari tabel terpilih (kelas PP 22/2021 atau set kustom).
+/// Temperatur dinilai sebagai deviasi dari suhu alamiah pos pada bulan sampling masing-masing.
+/// Nilai tersensor (< / > batas deteksi) diganti sesuai kebijakan substitusi.
+/// Mengembalikan (skor total, status, rincian per parameter).
+pub fn calculate_storet_collection(
+    dataset: &[KualitasAirRecord],
+    baku_mutu: &[BatasParameter],
+    tabel_suhu: &[SuhuAlamiah],
+    kebijakan: KebijakanSensor
+) -> (f64, String, Vec<RincianParameterStoret>) {
     // Validasi Dasar: STORET butuh data time series (minimal 2) untuk membentuk pola
     if dataset.len() < 2 {
-        return (0.0, "Data Kurang (Min. 2 Sampel)".to_string());
+        return (0.0, "Data Kurang (Min. 2 Sampel)".to_string(), Vec::new());
     }
 
-    let mut total_score = 0.0;
+    let mut rincian: Vec<RincianParameterStoret> = Vec::new();
 
     // Helper: Ekstrak nilai parameter spesifik dari seluruh dataset ke dalam Vec<f64>
-    // Contoh: Mengambil semua nilai 'TSS' dari Jan, Feb, Mar menjadi [30.0, 70.0, 40.0]
-    let get_values = |extractor: fn(&KualitasAirRecord) -> Option<f64>| -> Vec<f64> {
-        dataset.iter()
-            .filter_map(|record| extractor(record))
-            .collect()
+    // Contoh: Mengambil semua nilai 'tss' dari Jan, Feb, Mar menjadi [30.0, 70.0, 40.0]
+    // Temperatur (Deviasi) langsung dikonversi menjadi |T - suhu alamiah| per sampel.
+    // Jumlah nilai tersensor ikut dihitung untuk rincian.
+    let get_values = |batas: &BatasParameter| -> (Vec<f64>, usize) {
+        let mut tersensor = 0;
+        let values = dataset.iter()
+            .filter_map(|record| {
+                let nilai = record.nilai_hitung(&batas.parameter, kebijakan)?;
+                if record.tersensor.iter().any(|t| t.parameter == batas.parameter) { tersensor += 1; }
+                match batas.jenis {
+                    LimitType::Deviasi => Some((nilai - suhu_alamiah::suhu_untuk(tabel_suhu, record)).abs()),
+                    _ => Some(nilai),
+                }
+            })
+            .collect();
+        (values, tersensor)
     };
 
     // Helper: Hitung Skor per Parameter
     // Logika STORET (Kepmen LH 115):
     // 1. Cari Max, Min, Avg dari kumpulan data.
-    // 2. Bandingkan dengan Baku Mutu.
-    // 3. Jika Melanggar Max/Min -> Kena Denda.
-    // 4. Jika Melanggar Avg -> Kena Denda.
-    let mut calc_param = |values: Vec<f64>, baku: f64, kat: Kategori, limit: LimitType| {
+    // 2. Bandingkan MASING-MASING dengan Baku Mutu.
+    // 3. Setiap statistik yang melanggar -> Kena Denda sesuai tabel skor.
+    let mut calc_param = |(values, tersensor): (Vec<f64>, usize), batas: &BatasParameter, kat: Kategori| {
         if values.is_empty() { return; } // Skip jika tidak ada data untuk parameter ini
 
         // 1. Hitung Statistik Agregat
@@ -46,108 +89,50 @@ pub fn calculate_storet_collection(dataset: &Vec<KualitasAirRecord>) -> (f64, St
         let max_val = values.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
         let avg_val: f64 = values.iter().sum::<f64>() / values.len() as f64;
 
-        let mut violation_max_min = false;
-        let mut violation_avg = false;
-
-        // 2. Cek Pelanggaran
-        match limit {
-            LimitType::Max => {
-                if max_val > baku { violation_max_min = true; }
-                if avg_val > baku { violation_avg = true; }
-            },
-            LimitType::Min => {
-                if min_val < baku { violation_max_min = true; } // Khusus DO
-                if avg_val < baku { violation_avg = true; }
-            },
-            LimitType::Range => {
-                // Khusus pH dihandle terpisah karena punya range (min & max)
-            }
-        }
-
-        // 3. Terapkan Skor (Tabel Data < 10 Sampel)
-        // Fisika: Max/Min(-1) Avg(-3)
-        // Kimia : Max/Min(-2) Avg(-4)
-        // Biologi: Max/Min(-3) Avg(-5)
-        
-        // Catatan: Jika data >= 10, skornya beda. Tapi untuk aplikasi desktop ini
-        // kita asumsikan < 10 dulu (sesuai request "minimal 2").
-        // Jika mau perfect, tambahkan if values.len() >= 10 logic.
-        
-        if violation_max_min {
-            match kat {
-                Kategori::Fisika => total_score += -1.0,
-                Kategori::Kimia  => total_score += -2.0,
-                Kategori::Biologi=> total_score += -3.0,
-            }
-        }
-        if violation_avg {
-            match kat {
-                Kategori::Fisika => total_score += -3.0,
-                Kategori::Kimia  => total_score += -4.0,
-                Kategori::Biologi=> total_score += -5.0,
-            }
-        }
+        // 2. Cek Pelanggaran per statistik
+        let melebihi = |v: f64| batas.batas_atas.is_some_and(|atas| v > atas);
+        let kurang = |v: f64| batas.batas_bawah.is_some_and(|bawah| v < bawah);
+        let melanggar = |v: f64| match batas.jenis {
+            LimitType::Max | LimitType::Deviasi => melebihi(v),
+            LimitType::Min => kurang(v),                        // Khusus DO
+            LimitType::Range => kurang(v) || melebihi(v),       // Khusus pH
+        };
+
+        // 3. Terapkan Skor sesuai jumlah sampel parameter ini
+        let skor = SkorStoret::untuk(&kat, values.len());
+        let (melanggar_max, melanggar_min, melanggar_rata) = (melanggar(max_val), melanggar(min_val), melanggar(avg_val));
+
+        let mut skor_param = 0.0;
+        if melanggar_max { skor_param += skor.maks; }
+        if melanggar_min { skor_param += skor.min; }
+        if melanggar_rata { skor_param += skor.rata; }
+
+        rincian.push(RincianParameterStoret {
+            parameter: batas.parameter.clone(),
+            kategori: kat,
+            jenis: batas.jenis,
+            jumlah_sampel: values.len(),
+            jumlah_tersensor: tersensor,
+            min: min_val,
+            max: max_val,
+            rata: avg_val,
+            batas_bawah: batas.batas_bawah,
+            batas_atas: batas.batas_atas,
+            melanggar_max,
+            melanggar_min,
+            melanggar_rata,
+            skor: skor_param,
+        });
     };
 
     // =====================================================================
-    // EKSEKUSI PERHITUNGAN PER PARAMETER
+    // EKSEKUSI PERHITUNGAN PER PARAMETER (SESUAI TABEL BAKU MUTU)
     // =====================================================================
-
-    // --- 1. FISIKA ---
-    // Temperatur (Deviasi) - Agak kompleks karena butuh suhu alamiah.
-    // Kita skip dulu atau gunakan logika Max > 35 sementara.
-    calc_param(get_values(|d| d.temperatur), 35.0, Kategori::Fisika, LimitType::Max);
-    
-    calc_param(get_values(|d| d.tds), 1000.0, Kategori::Fisika, LimitType::Max);
-    calc_param(get_values(|d| d.tss), 50.0, Kategori::Fisika, LimitType::Max);
-    calc_param(get_values(|d| d.warna), 50.0, Kategori::Fisika, LimitType::Max);
-    calc_param(get_values(|d| d.kekeruhan), 25.0, Kategori::Fisika, LimitType::Max);
-
-    // --- 2. KIMIA ---
-    calc_param(get_values(|d| d.klorida), 300.0, Kategori::Kimia, LimitType::Max);
-    calc_param(get_values(|d| d.amoniak), 0.2, Kategori::Kimia, LimitType::Max);
-    calc_param(get_values(|d| d.nitrat), 10.0, Kategori::Kimia, LimitType::Max);
-    calc_param(get_values(|d| d.nitrit), 0.06, Kategori::Kimia, LimitType::Max);
-    calc_param(get_values(|d| d.fosfat), 0.2, Kategori::Kimia, LimitType::Max);
-    calc_param(get_values(|d| d.deterjen), 0.2, Kategori::Kimia, LimitType::Max);
-    calc_param(get_values(|d| d.arsen), 0.05, Kategori::Kimia, LimitType::Max);
-    calc_param(get_values(|d| d.mangan), 0.4, Kategori::Kimia, LimitType::Max);
-    calc_param(get_values(|d| d.tembaga), 0.02, Kategori::Kimia, LimitType::Max);
-    calc_param(get_values(|d| d.merkuri), 0.002, Kategori::Kimia, LimitType::Max);
-    calc_param(get_values(|d| d.sianida), 0.02, Kategori::Kimia, LimitType::Max);
-    calc_param(get_values(|d| d.fluorida), 1.5, Kategori::Kimia, LimitType::Max);
-    calc_param(get_values(|d| d.belerang), 0.002, Kategori::Kimia, LimitType::Max);
-    
-    // Organik
-    calc_param(get_values(|d| d.cod), 25.0, Kategori::Kimia, LimitType::Max);
-    calc_param(get_values(|d| d.bod), 3.0, Kategori::Kimia, LimitType::Max);
-    calc_param(get_values(|d| d.minyak_dan_lemak), 1.0, Kategori::Kimia, LimitType::Max);
-    calc_param(get_values(|d| d.fenol), 0.005, Kategori::Kimia, LimitType::Max);
-
-    // Oksigen (Min)
-    calc_param(get_values(|d| d.oksigen), 4.0, Kategori::Kimia, LimitType::Min);
-
-    // pH (Range 6-9) - Logic Manual
-    let ph_vals = get_values(|d| d.ph);
-    if !ph_vals.is_empty() {
-        let min_ph = ph_vals.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-        let max_ph = ph_vals.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-        let avg_ph = ph_vals.iter().sum::<f64>() / ph_vals.len() as f64;
-
-        let mut violation_max_min = false;
-        let mut violation_avg = false;
-
-        // Cek Min/Max (Jika ada satupun sampel < 6 atau > 9)
-        if min_ph < 6.0 || max_ph > 9.0 { violation_max_min = true; }
-        // Cek Avg (Jika rata-rata keluar range)
-        if avg_ph < 6.0 || avg_ph > 9.0 { violation_avg = true; }
-
-        if violation_max_min { total_score += -2.0; }
-        if violation_avg { total_score += -4.0; }
+    for batas in baku_mutu {
+        calc_param(get_values(batas), batas, kategori(&batas.parameter));
     }
 
-    // --- 3. BIOLOGI ---
-    calc_param(get_values(|d| d.total_coliform), 1000.0, Kategori::Biologi, LimitType::Max);
+    let total_score: f64 = rincian.iter().map(|r| r.skor).sum();
 
     // =====================================================================
     // STATUS MUTU
@@ -162,5 +147,5 @@ pub fn calculate_storet_collection(dataset: &Vec<KualitasAirRecord>) -> (f64, St
         "Kelas D (Buruk)".to_string()
     };
 
-    (total_score, status)
-}
\ No newline at end of file
+    (total_score, status, rincian)
+}
@@ -0,0 +1,36 @@
+use crate::models::kualitas_air::KualitasAirRecord;
+use crate::models::suhu_alamiah::SuhuAlamiah;
+use sqlx::SqlitePool;
+
+// Dipakai jika pos belum punya data suhu alamiah: asumsi rata-rata tropis
+pub const SUHU_ALAMIAH_DEFAULT: f64 = 27.0;
+
+/// Ambil seluruh suhu alamiah (tahunan & bulanan) milik satu pos
+pub async fn load_pos(pool: &SqlitePool, pos_id: Option<i64>) -> Result<Vec<SuhuAlamiah>, String> {
+    let Some(pos_id) = pos_id else { return Ok(Vec::new()) };
+
+    sqlx::query_as::<_, SuhuAlamiah>("SELECT * FROM suhu_alamiah WHERE pos_id = ? ORDER BY bulan")
+        .bind(pos_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Gagal mengambil suhu alamiah: {}", e))
+}
+
+/// Tentukan suhu alamiah untuk satu sampel:
+/// 1. Nilai bulan sampling untuk pos tersebut
+/// 2. Nilai sepanjang tahun (bulan = 0) untuk pos tersebut
+/// 3. SUHU_ALAMIAH_DEFAULT
+///
+/// Sampel yang belum punya pos_id (belum disimpan) memakai tabel apa adanya:
+/// pemanggil memuatnya dari pos yang cocok (lihat pos_service::cari_pos).
+pub fn suhu_untuk(tabel: &[SuhuAlamiah], record: &KualitasAirRecord) -> f64 {
+    let milik_pos = |s: &&SuhuAlamiah| record.pos_id.is_none_or(|id| id == s.pos_id);
+    let bulan = record.bulan_sampling();
+
+    tabel.iter()
+        .filter(milik_pos)
+        .find(|s| bulan.is_some_and(|b| s.bulan == b as i32))
+        .or_else(|| tabel.iter().filter(milik_pos).find(|s| s.bulan == 0))
+        .map(|s| s.suhu)
+        .unwrap_or(SUHU_ALAMIAH_DEFAULT)
+}
@@ -0,0 +1,69 @@
+use chrono::{Datelike, NaiveDate, NaiveTime};
+use regex::Regex;
+use std::sync::OnceLock;
+
+// Angka bulan dari nama bulan Indonesia / Inggris (lengkap atau singkatan, tanpa titik)
+fn bulan(nama: &str) -> Option<u32> {
+    let bulan = match nama.to_lowercase().trim_end_matches('.') {
+        "januari" | "jan" | "january" => 1,
+        "februari" | "feb" | "pebruari" | "peb" | "february" => 2,
+        "maret" | "mar" | "march" => 3,
+        "april" | "apr" => 4,
+        "mei" | "may" => 5,
+        "juni" | "jun" | "june" => 6,
+        "juli" | "jul" | "july" => 7,
+        "agustus" | "agu" | "agt" | "ags" | "aug" | "august" => 8,
+        "september" | "sep" | "sept" => 9,
+        "oktober" | "okt" | "oct" | "october" => 10,
+        "november" | "nov" | "nop" | "nopember" => 11,
+        "desember" | "des" | "dec" | "december" => 12,
+        _ => return None,
+    };
+    Some(bulan)
+}
+
+const NAMA_BULAN: [&str; 12] = [
+    "Januari", "Februari", "Maret", "April", "Mei", "Juni",
+    "Juli", "Agustus", "September", "Oktober", "November", "Desember",
+];
+
+/// Tanggal untuk dokumen resmi, misal "12 Maret 2024"
+pub fn format_panjang(tanggal: NaiveDate) -> String {
+    format!("{} {} {}", tanggal.day(), NAMA_BULAN[tanggal.month0() as usize], tanggal.year())
+}
+
+fn pola_tanggal() -> &'static [Regex; 3] {
+    static POLA: OnceLock<[Regex; 3]> = OnceLock::new();
+    POLA.get_or_init(|| [
+        // 2024-03-12 (ISO)
+        Regex::new(r"(?P<y>\d{4})-(?P<m>\d{1,2})-(?P<d>\d{1,2})").expect("pola ISO"),
+        // 12-03-2024 / 12/03/2024 / 12.03.2024
+        Regex::new(r"(?P<d>\d{1,2})[-/.](?P<m>\d{1,2})[-/.](?P<y>\d{4})").expect("pola angka"),
+        // 12 Maret 2024 / 12 Mar. 2024 / 12-Mar-2024
+        Regex::new(r"(?P<d>\d{1,2})[\s\-/.]+(?P<nama>[A-Za-z]{3,})\.?[\s\-/.,]+(?P<y>\d{4})").expect("pola nama bulan"),
+    ])
+}
+
+/// Baca tanggal dari teks laporan / input pengguna. Format yang dikenali:
+/// "2024-03-12", "12-03-2024", "12/03/2024", "12.03.2024", "12 Maret 2024", "12 Mar. 2024",
+/// termasuk jika diawali label seperti "Tgl." / "Tanggal :" atau nama hari.
+pub fn parse_tanggal(teks: &str) -> Option<NaiveDate> {
+    pola_tanggal().iter().find_map(|re| {
+        let caps = re.captures(teks)?;
+        let angka = |nama: &str| caps.name(nama)?.as_str().parse::<u32>().ok();
+        let m = match caps.name("nama") {
+            Some(nama) => bulan(nama.as_str())?,
+            None => angka("m")?,
+        };
+        NaiveDate::from_ymd_opt(angka("y")? as i32, m, angka("d")?)
+    })
+}
+
+/// Baca waktu "7.30" / "07:30" / "07:30:00" (detik diabaikan)
+pub fn parse_waktu(teks: &str) -> Option<NaiveTime> {
+    let (jam, sisa) = teks.trim().split_once([':', '.'])?;
+    let menit = sisa.split([':', '.']).next()?;
+    let jam: u32 = jam.trim().parse().ok()?;
+    let menit: u32 = menit.trim().parse().ok()?;
+    NaiveTime::from_hms_opt(jam, menit, 0)
+}
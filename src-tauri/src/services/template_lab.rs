@@ -0,0 +1,192 @@
+use crate::models::kualitas_air::{NAMA_METADATA, NAMA_PARAMETER};
+use crate::models::template_lab::TemplateLab;
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+// Template bawaan (format lab BBWS), selalu tersedia walau folder template kosong
+const TEMPLATE_DEFAULT: &str = include_str!("../../templates/default.json");
+
+// Folder template tambahan (di dalam AppLocalData), satu file .json per laboratorium
+const TEMPLATE_DIRNAME: &str = "template_lab";
+
+pub fn template_default() -> TemplateLab {
+    serde_json::from_str(TEMPLATE_DEFAULT).expect("templates/default.json tidak valid")
+}
+
+pub fn template_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_dir = app.path().app_local_data_dir()
+        .map_err(|e| format!("Gagal mendapatkan direktori app: {}", e))?;
+    Ok(app_dir.join(TEMPLATE_DIRNAME))
+}
+
+/// Ambil template bawaan + seluruh template di folder template.
+/// File yang tidak valid dilewati (dicatat di log) agar tidak menggagalkan impor.
+pub fn load_all(app: &AppHandle) -> Result<Vec<TemplateLab>, String> {
+    let mut templates = vec![template_default()];
+
+    let dir = template_dir(app)?;
+    if !dir.exists() {
+        return Ok(templates);
+    }
+
+    let entries = fs::read_dir(&dir)
+        .map_err(|e| format!("Gagal membaca folder template: {}", e))?;
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        match load_file(&path) {
+            Ok(template) => templates.push(template),
+            Err(e) => println!("⚠️ Template dilewati {:?}: {}", path, e),
+        }
+    }
+
+    Ok(templates)
+}
+
+fn load_file(path: &Path) -> Result<TemplateLab, String> {
+    let isi = fs::read_to_string(path)
+        .map_err(|e| format!("Gagal membaca file: {}", e))?;
+    let template: TemplateLab = serde_json::from_str(&isi)
+        .map_err(|e| format!("Format JSON tidak valid: {}", e))?;
+    validate(&template)?;
+    Ok(template)
+}
+
+/// Validasi isi template sebelum disimpan / dipakai
+pub fn validate(template: &TemplateLab) -> Result<(), String> {
+    if template.nama.trim().is_empty() {
+        return Err("Nama template wajib diisi".to_string());
+    }
+    if template.penanda_metode.iter().all(|p| p.trim().is_empty()) {
+        return Err("Penanda kolom metode wajib diisi".to_string());
+    }
+    if template.posisi_nilai == 0 || template.posisi_satuan == Some(0) {
+        return Err("Posisi kolom dihitung mulai dari 1".to_string());
+    }
+
+    for header in template.header.iter().chain(&template.header_sampel) {
+        if !NAMA_METADATA.contains(&header.field.as_str()) {
+            return Err(format!("Field header tidak dikenal: {}", header.field));
+        }
+        Regex::new(&header.pola)
+            .map_err(|e| format!("Pola header {} tidak valid: {}", header.field, e))?;
+    }
+
+    for alias in &template.parameter {
+        if !NAMA_PARAMETER.contains(&alias.field.as_str()) {
+            return Err(format!("Parameter tidak dikenal: {}", alias.field));
+        }
+        if alias.kata_kunci.is_empty() {
+            return Err(format!("Kata kunci parameter {} belum diisi", alias.field));
+        }
+    }
+
+    Ok(())
+}
+
+/// Pilih template untuk satu teks PDF: penanda deteksi terbanyak yang ditemukan.
+/// Jika tidak ada penanda yang cocok, template pertama (bawaan) yang dipakai.
+pub fn detect<'a>(templates: &'a [TemplateLab], content: &str) -> &'a TemplateLab {
+    let teks = content.to_lowercase();
+
+    let mut terpilih = &templates[0];
+    let mut skor_terbaik = 0;
+    for template in templates {
+        let skor = template.deteksi.iter()
+            .filter(|penanda| !penanda.trim().is_empty() && teks.contains(&penanda.to_lowercase()))
+            .count();
+        if skor > skor_terbaik {
+            terpilih = template;
+            skor_terbaik = skor;
+        }
+    }
+
+    terpilih
+}
+
+// Nama file dari nama template: huruf/angka saja, spasi jadi garis bawah
+fn nama_file(nama: &str) -> String {
+    let slug: String = nama.trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    format!("{}.json", slug)
+}
+
+// Nama berbeda bisa menghasilkan file yang sama ("Lab A" dan "lab-a" -> lab_a.json).
+// File yang sudah ada hanya boleh ditimpa/dihapus oleh template dengan nama yang sama.
+fn cek_pemilik_file(path: &Path, nama: &str) -> Result<(), String> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let pemilik = fs::read_to_string(path).ok()
+        .and_then(|isi| serde_json::from_str::<TemplateLab>(&isi).ok())
+        .map(|template| template.nama);
+    match pemilik {
+        Some(pemilik) if pemilik.trim() == nama.trim() => Ok(()),
+        Some(pemilik) => Err(format!(
+            "Nama '{}' bentrok dengan template tersimpan '{}', gunakan nama lain", nama.trim(), pemilik
+        )),
+        None => Err(format!(
+            "File {} sudah ada dan bukan template yang valid, gunakan nama lain",
+            path.file_name().unwrap_or_default().to_string_lossy()
+        )),
+    }
+}
+
+/// Simpan template ke folder template (menimpa template tersimpan dengan nama yang sama)
+pub fn save(app: &AppHandle, template: &TemplateLab) -> Result<PathBuf, String> {
+    validate(template)?;
+
+    let dir = template_dir(app)?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Gagal membuat folder template: {}", e))?;
+
+    let isi = serde_json::to_string_pretty(template)
+        .map_err(|e| format!("Gagal menyusun template: {}", e))?;
+    let path = dir.join(nama_file(&template.nama));
+    cek_pemilik_file(&path, &template.nama)?;
+    fs::write(&path, isi)
+        .map_err(|e| format!("Gagal menyimpan template: {}", e))?;
+
+    Ok(path)
+}
+
+/// Hapus template dari folder template (template bawaan tidak bisa dihapus)
+pub fn delete(app: &AppHandle, nama: &str) -> Result<(), String> {
+    let path = template_dir(app)?.join(nama_file(nama));
+    if !path.exists() {
+        return Err(format!("Template '{}' tidak ditemukan", nama));
+    }
+    cek_pemilik_file(&path, nama)?;
+    fs::remove_file(&path)
+        .map_err(|e| format!("Gagal menghapus template: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nama_file_bentrok_tidak_saling_menimpa() {
+        let dir = std::env::temp_dir().join(format!("template_lab_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let template = TemplateLab { nama: "Lab A".to_string(), ..template_default() };
+        let path = dir.join(nama_file("Lab A"));
+        assert_eq!(path, dir.join(nama_file("lab-a")));
+        fs::write(&path, serde_json::to_string(&template).unwrap()).unwrap();
+
+        assert!(cek_pemilik_file(&path, "Lab A").is_ok(), "simpan ulang dengan nama sama boleh");
+        assert!(cek_pemilik_file(&path, "lab-a").unwrap_err().contains("'Lab A'"));
+        assert!(cek_pemilik_file(&dir.join(nama_file("Lab B")), "Lab B").is_ok());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
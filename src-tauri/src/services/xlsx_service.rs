@@ -0,0 +1,478 @@
+use crate::models::baku_mutu::{BakuMutuClass, BatasParameter, LimitType};
+use crate::models::ekspor_csv::DesimalCsv;
+use crate::models::ekspor_xlsx::OpsiEksporXlsx;
+use crate::models::kualitas_air::{KualitasAirRecord, NAMA_PARAMETER};
+use crate::models::suhu_alamiah::SuhuAlamiah;
+use crate::services::{baku_mutu, csv_service, storet_calc, suhu_alamiah};
+use rust_xlsxwriter::{
+    Color, ConditionalFormatCell, ConditionalFormatCellRule, Format, FormatAlign, FormatBorder,
+    Note, RowNum, Workbook, Worksheet, XlsxError,
+};
+use chrono::NaiveDate;
+use std::borrow::Cow;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+
+const SHEET_DATA: &str = "Data Kualitas Air";
+const SHEET_RINGKASAN: &str = "Ringkasan per Pos";
+
+// Urutan status IP (Kepmen LH 115/2003) untuk kolom jumlah sampel per status
+const STATUS_IP: [&str; 4] = ["Memenuhi Baku Mutu", "Cemar Ringan", "Cemar Sedang", "Cemar Berat"];
+
+/// Baku mutu & suhu alamiah acuan untuk menandai nilai yang melampaui baku mutu.
+/// Tabel yang tidak dimuat memakai baku mutu kelas bawaan & suhu alamiah default.
+#[derive(Default)]
+pub struct AcuanBakuMutu {
+    baku_mutu: HashMap<(BakuMutuClass, Option<i64>), Vec<BatasParameter>>,
+    suhu: HashMap<i64, Vec<SuhuAlamiah>>,
+}
+
+impl AcuanBakuMutu {
+    /// Muat baku mutu yang dipakai perhitungan IP tiap sampel, baku mutu kelas pos (untuk STORET),
+    /// dan suhu alamiah tiap pos
+    pub async fn muat(pool: &sqlx::SqlitePool, data: &[KualitasAirRecord]) -> Result<Self, String> {
+        let mut acuan = AcuanBakuMutu::default();
+        for record in data {
+            for kunci in [kunci_ip(record), kunci_storet(record)] {
+                if let Entry::Vacant(slot) = acuan.baku_mutu.entry(kunci) {
+                    slot.insert(baku_mutu::resolve(pool, kunci.0, kunci.1).await?);
+                }
+            }
+            if let Some(pos_id) = record.pos_id {
+                if let Entry::Vacant(slot) = acuan.suhu.entry(pos_id) {
+                    slot.insert(suhu_alamiah::load_pos(pool, Some(pos_id)).await?);
+                }
+            }
+        }
+        Ok(acuan)
+    }
+
+    fn baku_mutu(&self, (kelas, set_id): (BakuMutuClass, Option<i64>)) -> Cow<'_, [BatasParameter]> {
+        match self.baku_mutu.get(&(kelas, set_id)) {
+            Some(batas) => Cow::Borrowed(batas),
+            None => Cow::Owned(baku_mutu::standar_kelas(kelas)),
+        }
+    }
+
+    fn suhu(&self, pos_id: Option<i64>) -> &[SuhuAlamiah] {
+        pos_id.and_then(|id| self.suhu.get(&id)).map_or(&[], |tabel| tabel)
+    }
+}
+
+// Baku mutu yang dipakai saat IP sampel dihitung: kelas IP -> kelas pos -> Kelas II
+fn kunci_ip(record: &KualitasAirRecord) -> (BakuMutuClass, Option<i64>) {
+    (record.kelas_ip.or(record.kelas_baku_mutu).unwrap_or_default(), record.baku_mutu_id)
+}
+
+// Baku mutu STORET mengikuti default command calculate_storet: kelas pos -> Kelas II
+fn kunci_storet(record: &KualitasAirRecord) -> (BakuMutuClass, Option<i64>) {
+    (record.kelas_baku_mutu.unwrap_or_default(), None)
+}
+
+// Aturan "melampaui baku mutu" satu sel parameter, setara dengan rasio > 1 di ip_calc
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Aturan {
+    Lebih(f64),         // Max
+    Kurang(f64),        // Min (DO)
+    DiLuar(f64, f64),   // Range (pH) & Deviasi temperatur (suhu alamiah ± deviasi)
+}
+
+fn aturan(batas: &BatasParameter, suhu_alamiah: f64) -> Option<Aturan> {
+    match batas.jenis {
+        LimitType::Max => batas.batas_atas.map(Aturan::Lebih),
+        LimitType::Min => batas.batas_bawah.map(Aturan::Kurang),
+        LimitType::Range => Some(Aturan::DiLuar(batas.batas_bawah?, batas.batas_atas?)),
+        LimitType::Deviasi => batas.batas_atas.map(|d| Aturan::DiLuar(suhu_alamiah - d, suhu_alamiah + d)),
+    }
+}
+
+fn gagal(e: XlsxError) -> String {
+    format!("Gagal menulis file .xlsx: {}", e)
+}
+
+// Format sel yang dipakai bersama oleh kedua sheet
+struct Gaya {
+    judul: Format,
+    tanggal: Format,
+    waktu: Format,
+    tanggal_waktu: Format,
+    desimal_2: Format,
+    melampaui: Format,
+    status_cemar: Format,
+}
+
+impl Gaya {
+    fn baru() -> Gaya {
+        Gaya {
+            judul: Format::new()
+                .set_bold()
+                .set_text_wrap()
+                .set_align(FormatAlign::Center)
+                .set_align(FormatAlign::VerticalCenter)
+                .set_background_color(Color::RGB(0xD9E1F2))
+                .set_border(FormatBorder::Thin),
+            tanggal: Format::new().set_num_format("dd/mm/yyyy"),
+            waktu: Format::new().set_num_format("hh:mm"),
+            tanggal_waktu: Format::new().set_num_format("dd/mm/yyyy hh:mm:ss"),
+            desimal_2: Format::new().set_num_format("0.00"),
+            melampaui: Format::new()
+                .set_font_color(Color::RGB(0x9C0006))
+                .set_background_color(Color::RGB(0xFFC7CE)),
+            status_cemar: Format::new().set_font_color(Color::RGB(0x9C0006)),
+        }
+    }
+}
+
+// Lebar kolom mengikuti judul (judul panjang dibungkus ke beberapa baris)
+fn tulis_judul(sheet: &mut Worksheet, judul: &[String], gaya: &Gaya) -> Result<(), String> {
+    for (kol, teks) in judul.iter().enumerate() {
+        let kol = kol as u16;
+        sheet.write_string_with_format(0, kol, teks, &gaya.judul).map_err(gagal)?;
+        sheet.set_column_width(kol, (teks.chars().count() as f64 * 0.9).clamp(10.0, 24.0)).map_err(gagal)?;
+    }
+    sheet.set_row_height(0, 45).map_err(gagal)?;
+    sheet.set_freeze_panes(1, 0).map_err(gagal)?;
+    Ok(())
+}
+
+// Tulis satu sel sheet data dengan tipe aslinya (angka, tanggal, waktu, teks)
+fn tulis_sel(
+    sheet: &mut Worksheet,
+    baris: RowNum,
+    kol: u16,
+    record: &KualitasAirRecord,
+    kunci: &str,
+    gaya: &Gaya
+) -> Result<(), XlsxError> {
+    let angka = |sheet: &mut Worksheet, nilai: Option<f64>, format: Option<&Format>| match (nilai, format) {
+        (Some(n), Some(f)) => sheet.write_number_with_format(baris, kol, n, f).map(|_| ()),
+        (Some(n), None) => sheet.write_number(baris, kol, n).map(|_| ()),
+        (None, _) => Ok(()),
+    };
+
+    if NAMA_PARAMETER.contains(&kunci) {
+        return angka(sheet, record.nilai_parameter(kunci), None);
+    }
+    match kunci {
+        "id" => angka(sheet, record.id.map(|id| id as f64), None),
+        "tahun" => angka(sheet, record.tahun.map(f64::from), None),
+        "nilai_ip" => angka(sheet, record.nilai_ip, Some(&gaya.desimal_2)),
+        "nilai_storet" => angka(sheet, record.nilai_storet, None),
+        "tanggal_sampling" => match record.tanggal_sampling {
+            Some(t) => sheet.write_date_with_format(baris, kol, t, &gaya.tanggal).map(|_| ()),
+            None => Ok(()),
+        },
+        "waktu_sampling" => match record.waktu_sampling {
+            Some(w) => sheet.write_time_with_format(baris, kol, w, &gaya.waktu).map(|_| ()),
+            None => Ok(()),
+        },
+        "created_at" => match record.created_at {
+            Some(t) => sheet.write_datetime_with_format(baris, kol, t, &gaya.tanggal_waktu).map(|_| ()),
+            None => Ok(()),
+        },
+        _ => {
+            let teks = csv_service::sel(record, kunci, DesimalCsv::Titik);
+            if teks.is_empty() { return Ok(()) }
+            if kunci == "status_ip" && teks.starts_with("Cemar") {
+                sheet.write_string_with_format(baris, kol, teks, &gaya.status_cemar).map(|_| ())
+            } else {
+                sheet.write_string(baris, kol, teks).map(|_| ())
+            }
+        }
+    }
+}
+
+// Aturan satu sel parameter. Excel menganggap sel kosong bernilai 0, jadi sel tanpa nilai
+// (dan nilai tersensor yang ditulis sebagai batas deteksinya) tidak diberi aturan.
+fn aturan_sel(record: &KualitasAirRecord, kunci: &str, batas: &[BatasParameter], suhu_alamiah: f64) -> Option<Aturan> {
+    if record.nilai_parameter(kunci).is_none() || record.tersensor.iter().any(|t| t.parameter == kunci) {
+        return None;
+    }
+    batas.iter().find(|b| b.parameter == kunci).and_then(|b| aturan(b, suhu_alamiah))
+}
+
+// Gabungkan aturan per baris data (baris 1 = sampel pertama) menjadi blok baris berurutan
+// yang memakai aturan sama, agar jumlah aturan conditional formatting tetap kecil
+fn blok_aturan(per_baris: impl IntoIterator<Item = Option<Aturan>>) -> Vec<(RowNum, RowNum, Aturan)> {
+    let mut hasil = Vec::new();
+    let mut blok: Option<(RowNum, RowNum, Aturan)> = None;
+    for (idx, aturan) in per_baris.into_iter().enumerate() {
+        let baris = idx as RowNum + 1;
+        match (&mut blok, aturan) {
+            (Some((_, akhir, lama)), Some(baru)) if *lama == baru => *akhir = baris,
+            (_, aturan) => {
+                hasil.extend(blok.take());
+                blok = aturan.map(|a| (baris, baris, a));
+            }
+        }
+    }
+    hasil.extend(blok);
+    hasil
+}
+
+// Conditional formatting satu blok baris berurutan yang memakai aturan sama
+fn tandai(sheet: &mut Worksheet, awal: RowNum, akhir: RowNum, kol: u16, aturan: Aturan, gaya: &Gaya) -> Result<(), String> {
+    let rule = match aturan {
+        Aturan::Lebih(batas) => ConditionalFormatCellRule::GreaterThan(batas),
+        Aturan::Kurang(batas) => ConditionalFormatCellRule::LessThan(batas),
+        Aturan::DiLuar(bawah, atas) => ConditionalFormatCellRule::NotBetween(bawah, atas),
+    };
+    let format = ConditionalFormatCell::new().set_rule(rule).set_format(&gaya.melampaui);
+    sheet.add_conditional_format(awal, kol, akhir, kol, &format).map_err(gagal)?;
+    Ok(())
+}
+
+// Sheet 1: satu baris per sampel. Nilai parameter yang melampaui baku mutu diberi warna lewat
+// conditional formatting, sehingga tanda ikut berubah jika nilai dikoreksi di Excel.
+// Nilai tersensor (< / > batas deteksi) ditulis sebagai batas deteksinya dengan catatan sel, tanpa aturan.
+fn sheet_data(
+    workbook: &mut Workbook,
+    data: &[KualitasAirRecord],
+    kolom: &[String],
+    acuan: &AcuanBakuMutu,
+    gaya: &Gaya
+) -> Result<(), String> {
+    let judul: Vec<String> = kolom.iter()
+        .map(|k| csv_service::judul_kolom(k).ok_or_else(|| format!("Kolom export tidak dikenal: {}", k)))
+        .collect::<Result<_, _>>()?;
+
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(SHEET_DATA).map_err(gagal)?;
+    tulis_judul(sheet, &judul, gaya)?;
+
+    for (idx, record) in data.iter().enumerate() {
+        let baris = idx as RowNum + 1;
+        for (kol, kunci) in kolom.iter().enumerate() {
+            tulis_sel(sheet, baris, kol as u16, record, kunci, gaya).map_err(gagal)?;
+        }
+        for sensor in &record.tersensor {
+            let Some(kol) = kolom.iter().position(|k| *k == sensor.parameter) else { continue };
+            let note = Note::new(format!("Nilai tersensor: {}{}", sensor.kualifier.simbol(), sensor.batas_deteksi))
+                .set_author("Data Hidrologi");
+            sheet.insert_note(baris, kol as u16, &note).map_err(gagal)?;
+        }
+    }
+
+    // Aturan per sel parameter, digabung per blok baris berurutan
+    let acuan_record: Vec<(Cow<'_, [BatasParameter]>, f64)> = data.iter()
+        .map(|record| (
+            acuan.baku_mutu(kunci_ip(record)),
+            suhu_alamiah::suhu_untuk(acuan.suhu(record.pos_id), record),
+        ))
+        .collect();
+
+    for (kol, kunci) in kolom.iter().enumerate().filter(|(_, k)| NAMA_PARAMETER.contains(&k.as_str())) {
+        let per_baris = data.iter().zip(&acuan_record)
+            .map(|(record, (batas, suhu))| aturan_sel(record, kunci, batas, *suhu));
+        for (awal, akhir, aturan) in blok_aturan(per_baris) {
+            tandai(sheet, awal, akhir, kol as u16, aturan, gaya)?;
+        }
+    }
+
+    if !judul.is_empty() {
+        sheet.autofilter(0, 0, data.len() as RowNum, judul.len() as u16 - 1).map_err(gagal)?;
+    }
+    Ok(())
+}
+
+// Isi satu sel sheet ringkasan (None / teks kosong = sel dibiarkan kosong)
+enum Isi<'a> {
+    Teks(String, Option<&'a Format>),
+    Angka(Option<f64>, Option<&'a Format>),
+    Tanggal(Option<NaiveDate>),
+}
+
+impl Isi<'_> {
+    fn tulis(&self, sheet: &mut Worksheet, baris: RowNum, kol: u16, gaya: &Gaya) -> Result<(), XlsxError> {
+        match self {
+            Isi::Teks(teks, _) if teks.is_empty() => {}
+            Isi::Teks(teks, Some(format)) => { sheet.write_string_with_format(baris, kol, teks, format)?; }
+            Isi::Teks(teks, None) => { sheet.write_string(baris, kol, teks)?; }
+            Isi::Angka(Some(nilai), Some(format)) => { sheet.write_number_with_format(baris, kol, *nilai, format)?; }
+            Isi::Angka(Some(nilai), None) => { sheet.write_number(baris, kol, *nilai)?; }
+            Isi::Tanggal(Some(tanggal)) => { sheet.write_date_with_format(baris, kol, tanggal, &gaya.tanggal)?; }
+            Isi::Angka(None, _) | Isi::Tanggal(None) => {}
+        }
+        Ok(())
+    }
+}
+
+// Sheet 2: rekap per pos. IP dari nilai tersimpan tiap sampel; STORET dihitung ulang dari
+// sampel yang ikut diexport (sesuai filter) tanpa menulis ke database.
+fn sheet_ringkasan(
+    workbook: &mut Workbook,
+    data: &[KualitasAirRecord],
+    acuan: &AcuanBakuMutu,
+    gaya: &Gaya
+) -> Result<(), String> {
+    let mut per_pos: BTreeMap<&str, Vec<KualitasAirRecord>> = BTreeMap::new();
+    for record in data {
+        per_pos.entry(record.nama_pos.as_deref().unwrap_or("-")).or_default().push(record.clone());
+    }
+
+    let judul: Vec<String> = [
+        "Nama Pos", "DAS", "Kabupaten/Kota", "Jumlah Sampel", "Tanggal Awal", "Tanggal Akhir",
+        "IP Rata-rata", "IP Minimum", "IP Maksimum", "IP Terakhir", "Status IP Terakhir",
+    ].iter().map(|j| j.to_string())
+        .chain(STATUS_IP.iter().map(|s| format!("Jumlah {}", s)))
+        .chain(["Kelas Baku Mutu STORET", "Skor STORET", "Status STORET"].iter().map(|j| j.to_string()))
+        .collect();
+
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(SHEET_RINGKASAN).map_err(gagal)?;
+    tulis_judul(sheet, &judul, gaya)?;
+
+    for (idx, (nama_pos, sampel)) in per_pos.iter().enumerate() {
+        let baris = idx as RowNum + 1;
+        let pertama = &sampel[0];
+        let ip: Vec<f64> = sampel.iter().filter_map(|r| r.nilai_ip).collect();
+        let terakhir = sampel.iter().max_by_key(|r| (r.tanggal_sampling, r.waktu_sampling, r.id));
+        let tanggal = sampel.iter().filter_map(|r| r.tanggal_sampling);
+
+        // Satu nama pos bisa terdaftar sebagai beberapa pos (koordinat berbeda): gabungkan suhu alamiahnya
+        let mut pos_ids: Vec<i64> = sampel.iter().filter_map(|r| r.pos_id).collect();
+        pos_ids.sort_unstable();
+        pos_ids.dedup();
+        let tabel_suhu: Vec<SuhuAlamiah> = pos_ids.into_iter()
+            .flat_map(|id| acuan.suhu(Some(id)).iter().cloned())
+            .collect();
+
+        let kunci = kunci_storet(pertama);
+        let kebijakan = pertama.kebijakan_sensor.unwrap_or_default();
+        let (skor, status_storet, _) = storet_calc::calculate_storet_collection(
+            sampel, &acuan.baku_mutu(kunci), &tabel_suhu, kebijakan
+        );
+
+        let (ip_rata, ip_min, ip_maks) = if ip.is_empty() {
+            (None, None, None)
+        } else {
+            (
+                Some(ip.iter().sum::<f64>() / ip.len() as f64),
+                Some(ip.iter().copied().fold(f64::INFINITY, f64::min)),
+                Some(ip.iter().copied().fold(f64::NEG_INFINITY, f64::max)),
+            )
+        };
+        let status_ip = terakhir.and_then(|r| r.status_ip.clone());
+        let format_status = |status: &str| status.starts_with("Cemar").then_some(&gaya.status_cemar);
+
+        let mut isi = vec![
+            Isi::Teks(nama_pos.to_string(), None),
+            Isi::Teks(pertama.das.clone().unwrap_or_default(), None),
+            Isi::Teks(pertama.kabupaten.clone().unwrap_or_default(), None),
+            Isi::Angka(Some(sampel.len() as f64), None),
+            Isi::Tanggal(tanggal.clone().min()),
+            Isi::Tanggal(tanggal.max()),
+            Isi::Angka(ip_rata, Some(&gaya.desimal_2)),
+            Isi::Angka(ip_min, Some(&gaya.desimal_2)),
+            Isi::Angka(ip_maks, Some(&gaya.desimal_2)),
+            Isi::Angka(terakhir.and_then(|r| r.nilai_ip), Some(&gaya.desimal_2)),
+            Isi::Teks(status_ip.clone().unwrap_or_default(), status_ip.as_deref().and_then(format_status)),
+        ];
+        isi.extend(STATUS_IP.iter().map(|status| {
+            let jumlah = sampel.iter().filter(|r| r.status_ip.as_deref() == Some(status)).count();
+            Isi::Angka(Some(jumlah as f64), None)
+        }));
+        // STORET butuh minimal 2 sampel; skor 0 dari data kurang tidak ditulis agar tidak terbaca "Baik Sekali"
+        isi.extend([
+            Isi::Teks(kunci.0.label().to_string(), None),
+            Isi::Angka((sampel.len() >= 2).then_some(skor), None),
+            Isi::Teks(status_storet, None),
+        ]);
+
+        for (kol, isi) in isi.iter().enumerate() {
+            isi.tulis(sheet, baris, kol as u16, gaya).map_err(gagal)?;
+        }
+    }
+
+    // Keterangan di bawah tabel
+    let baris = per_pos.len() as RowNum + 2;
+    let keterangan = [
+        format!("Sel parameter berwarna merah pada sheet '{}' melampaui baku mutu yang dipakai saat IP sampel dihitung.", SHEET_DATA),
+        "Skor STORET dihitung ulang dari sampel yang diexport dengan baku mutu kelas pos (tidak disimpan ke database).".to_string(),
+    ];
+    for (i, teks) in keterangan.iter().enumerate() {
+        sheet.write_string(baris + i as RowNum, 0, teks).map_err(gagal)?;
+    }
+    Ok(())
+}
+
+pub fn export_to_xlsx(
+    data: &[KualitasAirRecord],
+    opsi: &OpsiEksporXlsx,
+    acuan: &AcuanBakuMutu,
+    file_path: &str
+) -> Result<(), String> {
+    let kolom: Vec<String> = if opsi.kolom.is_empty() {
+        csv_service::daftar_kolom().into_iter().map(|k| k.kunci).collect()
+    } else {
+        opsi.kolom.clone()
+    };
+
+    let gaya = Gaya::baru();
+    let mut workbook = Workbook::new();
+    sheet_data(&mut workbook, data, &kolom, acuan, &gaya)?;
+    sheet_ringkasan(&mut workbook, data, acuan, &gaya)?;
+
+    workbook.save(file_path)
+        .map_err(|e| format!("Gagal menyimpan file .xlsx: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::nilai_tersensor::{Kualifier, NilaiTersensor};
+
+    fn batas(jenis: LimitType, bawah: Option<f64>, atas: Option<f64>) -> BatasParameter {
+        BatasParameter {
+            parameter: "tss".to_string(),
+            jenis,
+            batas_bawah: bawah,
+            batas_atas: atas,
+            satuan: None,
+            rujukan: None,
+        }
+    }
+
+    #[test]
+    fn aturan_per_jenis_batas() {
+        assert_eq!(aturan(&batas(LimitType::Max, None, Some(50.0)), 25.0), Some(Aturan::Lebih(50.0)));
+        assert_eq!(aturan(&batas(LimitType::Min, Some(4.0), None), 25.0), Some(Aturan::Kurang(4.0)));
+        assert_eq!(aturan(&batas(LimitType::Range, Some(6.0), Some(9.0)), 25.0), Some(Aturan::DiLuar(6.0, 9.0)));
+        // Deviasi: suhu alamiah ± deviasi
+        assert_eq!(aturan(&batas(LimitType::Deviasi, None, Some(3.0)), 27.0), Some(Aturan::DiLuar(24.0, 30.0)));
+        // Batas belum lengkap: tanpa aturan
+        assert_eq!(aturan(&batas(LimitType::Range, Some(6.0), None), 25.0), None);
+        assert_eq!(aturan(&batas(LimitType::Max, None, None), 25.0), None);
+    }
+
+    #[test]
+    fn aturan_digabung_per_blok_baris_berurutan() {
+        let kelas_ii = [batas(LimitType::Max, None, Some(50.0))];
+        let kelas_iii = [batas(LimitType::Max, None, Some(100.0))];
+        let tss = |nilai: Option<f64>| KualitasAirRecord { tss: nilai, ..Default::default() };
+        let tersensor = KualitasAirRecord {
+            tss: Some(5.0),
+            tersensor: vec![NilaiTersensor { parameter: "tss".to_string(), kualifier: Kualifier::KurangDari, batas_deteksi: 5.0 }],
+            ..Default::default()
+        };
+
+        let sampel = [
+            (tss(Some(20.0)), &kelas_ii[..]),
+            (tss(Some(80.0)), &kelas_ii[..]),
+            (tss(Some(30.0)), &kelas_iii[..]),
+            (tss(None), &kelas_iii[..]),    // Kosong: tanpa aturan, blok terputus
+            (tss(Some(40.0)), &kelas_iii[..]),
+            (tersensor, &kelas_iii[..]),    // Tersensor: tanpa aturan
+            (tss(Some(60.0)), &kelas_iii[..]),
+        ];
+        let per_baris = sampel.iter().map(|(record, batas)| aturan_sel(record, "tss", batas, 25.0));
+
+        assert_eq!(blok_aturan(per_baris), [
+            (1, 2, Aturan::Lebih(50.0)),
+            (3, 3, Aturan::Lebih(100.0)),
+            (5, 5, Aturan::Lebih(100.0)),
+            (7, 7, Aturan::Lebih(100.0)),
+        ]);
+        assert!(blok_aturan([None, None]).is_empty());
+    }
+}
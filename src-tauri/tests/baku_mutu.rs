@@ -0,0 +1,42 @@
+// Validasi set baku mutu kustom (baku_mutu::validate) sebelum disimpan ke database.
+
+use data_hidrologi_lib::models::baku_mutu::{BakuMutuSet, BatasParameter, LimitType};
+use data_hidrologi_lib::services::baku_mutu;
+
+fn batas(parameter: &str, jenis: LimitType, bawah: Option<f64>, atas: Option<f64>) -> BatasParameter {
+    BatasParameter {
+        parameter: parameter.to_string(),
+        jenis,
+        batas_bawah: bawah,
+        batas_atas: atas,
+        satuan: None,
+        rujukan: None,
+    }
+}
+
+fn set(parameter: Vec<BatasParameter>) -> BakuMutuSet {
+    BakuMutuSet {
+        id: None,
+        nama: "Perda Sungai X".to_string(),
+        rujukan: None,
+        keterangan: None,
+        parameter,
+        created_at: None,
+    }
+}
+
+#[test]
+fn validasi_batas_set_kustom() {
+    let ph = batas("ph", LimitType::Range, Some(6.0), Some(9.0));
+    let bod = batas("bod", LimitType::Max, None, Some(3.0));
+    assert!(baku_mutu::validate(&set(vec![ph.clone(), bod.clone()])).is_ok());
+
+    for (kasus, parameter) in [
+        ("batas atas nol", vec![batas("bod", LimitType::Max, None, Some(0.0))]),
+        ("batas bawah Min nol", vec![batas("do", LimitType::Min, Some(0.0), None)]),
+        ("rentang terbalik", vec![batas("ph", LimitType::Range, Some(9.0), Some(6.0))]),
+        ("parameter ganda", vec![bod.clone(), ph, batas("bod", LimitType::Max, None, Some(6.0))]),
+    ] {
+        assert!(baku_mutu::validate(&set(parameter)).is_err(), "{}", kasus);
+    }
+}
@@ -0,0 +1,108 @@
+// Impor CSV / Excel (impor_tabel): pembacaan file, saran pemetaan & validasi per baris.
+// Fixture di tests/fixtures/impor meniru spreadsheet lama: baris judul laporan di atas tabel,
+// desimal koma, tanggal campuran, satuan µg/L, dan beberapa baris bermasalah.
+
+use chrono::{NaiveDate, NaiveTime};
+use data_hidrologi_lib::models::ekspor_csv::DesimalCsv;
+use data_hidrologi_lib::models::impor_tabel::PemetaanImpor;
+use data_hidrologi_lib::models::nilai_tersensor::Kualifier;
+use data_hidrologi_lib::services::impor_tabel;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+fn fixture(nama: &str) -> String {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/impor").join(nama).to_string_lossy().to_string()
+}
+
+fn pemetaan_spreadsheet_lama() -> PemetaanImpor {
+    PemetaanImpor {
+        nama: "Spreadsheet lama".to_string(),
+        lembar: None,
+        baris_header: 2,
+        pemisah: None,
+        desimal: DesimalCsv::Koma,
+        kolom: BTreeMap::new(),
+    }
+}
+
+#[test]
+fn saran_pemetaan_dari_judul_kolom() {
+    let path = fixture("spreadsheet_lama.csv");
+    let tabel = impor_tabel::baca(&path, &pemetaan_spreadsheet_lama()).expect("file gagal dibaca");
+
+    assert_eq!(tabel.judul, ["Nama Pos", "Tanggal", "Jam", "pH", "Besi [µg/L]", "BOD", "Fenol"]);
+    let saran = impor_tabel::ringkas(&path, &tabel).saran;
+    for (field, judul) in [
+        ("nama_pos", "Nama Pos"), ("tanggal_sampling", "Tanggal"), ("waktu_sampling", "Jam"),
+        ("ph", "pH"), ("besi", "Besi [µg/L]"), ("bod", "BOD"), ("fenol", "Fenol"),
+    ] {
+        assert_eq!(saran.get(field).map(String::as_str), Some(judul), "{}", field);
+    }
+}
+
+#[test]
+fn validasi_per_baris() {
+    let path = fixture("spreadsheet_lama.csv");
+    let mut pemetaan = pemetaan_spreadsheet_lama();
+    let tabel = impor_tabel::baca(&path, &pemetaan).expect("file gagal dibaca");
+    pemetaan.kolom = impor_tabel::saran_pemetaan(&tabel.judul);
+
+    let hasil = impor_tabel::validasi(&tabel, &pemetaan).expect("pemetaan tidak valid");
+    let nomor: Vec<usize> = hasil.baris.iter().map(|b| b.baris).collect();
+    assert_eq!(nomor, [3, 4, 5, 7, 8], "baris kosong harus dilewati");
+    assert_eq!((hasil.jumlah_valid, hasil.jumlah_gagal), (2, 3));
+    assert_eq!(hasil.peringatan.len(), 1, "konversi µg/L dicatat sekali per kolom");
+
+    // Desimal koma, µg/L -> mg/L, nilai tersensor, tahun dari tanggal
+    let record = hasil.baris[0].record.as_ref().expect("baris 3 valid");
+    assert_eq!(record.tanggal_sampling, NaiveDate::from_ymd_opt(2019, 3, 5));
+    assert_eq!(record.waktu_sampling, NaiveTime::from_hms_opt(8, 30, 0));
+    assert_eq!(record.tahun, Some(2019));
+    assert_eq!((record.ph, record.besi, record.bod, record.fenol), (Some(7.2), Some(0.15), Some(2.5), Some(0.002)));
+    assert_eq!(record.tersensor.len(), 1);
+    assert_eq!(record.tersensor[0].kualifier, Kualifier::KurangDari);
+
+    // Pos, tanggal & waktu sama dengan baris 3
+    assert!(hasil.baris[1].duplikat);
+
+    // pH di luar rentang, angka & tanggal tidak terbaca, pos kosong
+    assert!(hasil.baris[2].error[0].contains("pH 15"));
+    assert_eq!(hasil.baris[3].error.len(), 2);
+    assert_eq!(hasil.baris[4].error, ["Nama Pos wajib diisi"]);
+}
+
+#[test]
+fn pemetaan_wajib_pos_dan_tanggal() {
+    let path = fixture("spreadsheet_lama.csv");
+    let mut pemetaan = pemetaan_spreadsheet_lama();
+    let tabel = impor_tabel::baca(&path, &pemetaan).expect("file gagal dibaca");
+    pemetaan.kolom.insert("nama_pos".to_string(), "Nama Pos".to_string());
+
+    let error = impor_tabel::validasi(&tabel, &pemetaan).expect_err("tanggal belum dipetakan");
+    assert!(error.contains("Tanggal Sampling"), "{}", error);
+}
+
+#[test]
+fn desimal_titik_hanya_membuang_pemisah_ribuan() {
+    let path = fixture("desimal_titik.csv");
+    let mut pemetaan = PemetaanImpor {
+        nama: "Ekspor sistem lain".to_string(),
+        baris_header: 1,
+        ..pemetaan_spreadsheet_lama()
+    };
+    pemetaan.desimal = DesimalCsv::Titik;
+    let tabel = impor_tabel::baca(&path, &pemetaan).expect("file gagal dibaca");
+    pemetaan.kolom = impor_tabel::saran_pemetaan(&tabel.judul);
+
+    let hasil = impor_tabel::validasi(&tabel, &pemetaan).expect("pemetaan tidak valid");
+    assert_eq!((hasil.jumlah_valid, hasil.jumlah_gagal), (1, 2));
+
+    let record = hasil.baris[0].record.as_ref().expect("baris 2 valid");
+    assert_eq!((record.tds, record.besi), (Some(1234.5), Some(0.15)));
+
+    // "0,15" dan "1,5" bukan pemisah ribuan: ditolak, bukan dibaca 15
+    for baris in &hasil.baris[1..] {
+        assert_eq!(baris.error.len(), 1, "{:?}", baris.error);
+        assert!(baris.error[0].contains("desimal: koma"), "{}", baris.error[0]);
+    }
+}
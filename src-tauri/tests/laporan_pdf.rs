@@ -0,0 +1,67 @@
+// Laporan PDF (laporan_pdf): dokumen dibaca ulang dengan pdf-extract untuk memastikan
+// kop, identitas sampel, nilai tersensor, tanda melampaui & status IP tercetak.
+
+use chrono::{NaiveDate, NaiveTime};
+use data_hidrologi_lib::models::baku_mutu::BakuMutuClass;
+use data_hidrologi_lib::models::kualitas_air::KualitasAirRecord;
+use data_hidrologi_lib::models::nilai_tersensor::{KebijakanSensor, Kualifier, NilaiTersensor};
+use data_hidrologi_lib::services::laporan_pdf::{self, DataLaporan};
+use data_hidrologi_lib::services::{baku_mutu, ip_calc};
+
+#[test]
+fn laporan_pdf_memuat_identitas_tabel_dan_status_ip() {
+    let record = KualitasAirRecord {
+        id: Some(17),
+        nama_pos: Some("Pos Konaweha Hilir".to_string()),
+        das: Some("Konaweha".to_string()),
+        kabupaten: Some("Konawe".to_string()),
+        provinsi: Some("Sulawesi Tenggara".to_string()),
+        tanggal_sampling: NaiveDate::from_ymd_opt(2024, 3, 12),
+        waktu_sampling: NaiveTime::from_hms_opt(9, 30, 0),
+        laboratorium: Some("Laboratorium Lingkungan".to_string()),
+        temperatur: Some(28.0),
+        ph: Some(7.1),
+        oksigen: Some(5.2),
+        tss: Some(180.0),
+        bod: Some(2.0),
+        fenol: Some(0.002),
+        tersensor: vec![NilaiTersensor {
+            parameter: "fenol".to_string(),
+            kualifier: Kualifier::KurangDari,
+            batas_deteksi: 0.002,
+        }],
+        ..Default::default()
+    };
+    let batas = baku_mutu::standar_kelas(BakuMutuClass::KelasII);
+    let hasil_ip = ip_calc::calculate_ip(&record, &batas, &[], KebijakanSensor::default());
+    assert!(hasil_ip.status.starts_with("Cemar"), "TSS 180 mg/L harus membuat sampel tercemar");
+
+    let data = DataLaporan {
+        record: &record,
+        baku_mutu: &batas,
+        nama_baku_mutu: "Kelas II (PP No. 22 Tahun 2021)".to_string(),
+        hasil_ip: &hasil_ip,
+        tanggal_cetak: NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(),
+    };
+    let pdf = laporan_pdf::buat_laporan(&data).expect("laporan gagal disusun");
+    assert!(pdf.starts_with(b"%PDF-1.5"));
+    assert!(pdf.len() < 100_000, "laporan satu halaman {} byte: logo harus versi yang diperkecil", pdf.len());
+
+    let teks = pdf_extract::extract_text_from_mem(&pdf).expect("PDF tidak terbaca");
+    for isi in [
+        "KEMENTERIAN PEKERJAAN UMUM",
+        "LAPORAN HASIL ANALISA KUALITAS AIR",
+        "Pos Konaweha Hilir",
+        "12 Maret 2024",
+        "09:30",
+        "180 *",
+        "Melampaui",
+        "<0.002",
+        &hasil_ip.status,
+        &format!("{:.2}", hasil_ip.nilai_ip),
+        "Kelas II (PP No. 22 Tahun 2021)",
+        "Halaman 1 dari",
+    ] {
+        assert!(teks.contains(isi), "'{}' tidak ditemukan di laporan:\n{}", isi, teks);
+    }
+}
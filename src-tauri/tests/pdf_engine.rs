@@ -0,0 +1,116 @@
+// Regresi parser laporan lab (pdf_engine).
+// Setiap fixture di tests/fixtures/pdf berpasangan dengan file .json berisi record yang diharapkan
+// (array, satu record per kolom sampel):
+// - *.txt : teks hasil ekstraksi PDF, diuji lewat parse_text (tanpa membaca PDF)
+// - *.pdf : PDF sintetis kecil, diuji lewat parse_pdf (ekstraksi teks + parsing)
+//
+// Setelah perubahan parser yang disengaja, tulis ulang seluruh file .json dengan:
+//   PERBARUI_GOLDEN=1 cargo test --test pdf_engine
+// lalu periksa diff-nya sebelum commit.
+
+use chrono::NaiveDate;
+use data_hidrologi_lib::models::import_pdf::HasilEkstraksi;
+use data_hidrologi_lib::services::{pdf_engine, tanggal, template_lab};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn fixture_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/pdf")
+}
+
+// Seluruh fixture dengan ekstensi tertentu, terurut nama file
+fn daftar_fixture(ekstensi: &str) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(fixture_dir())
+        .expect("folder fixture tidak bisa dibaca")
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == ekstensi))
+        .collect();
+    files.sort();
+    files
+}
+
+// Bandingkan record hasil parsing dengan file .json pasangannya.
+// Mengembalikan daftar perbedaan per sampel & field (kosong jika sama).
+fn bandingkan_golden(fixture: &Path, hasil: &[HasilEkstraksi]) -> Vec<String> {
+    let records: Vec<Value> = hasil.iter()
+        .map(|h| serde_json::to_value(&h.record).expect("record tidak bisa diserialisasi"))
+        .collect();
+    let golden = fixture.with_extension("json");
+    let nama = fixture.file_name().unwrap_or_default().to_string_lossy();
+
+    if std::env::var_os("PERBARUI_GOLDEN").is_some() {
+        let isi = serde_json::to_string_pretty(&records).expect("golden tidak bisa diserialisasi");
+        fs::write(&golden, isi + "\n").expect("golden tidak bisa ditulis");
+        return Vec::new();
+    }
+
+    let Ok(isi) = fs::read_to_string(&golden) else {
+        return vec![format!("{}: file {} belum ada (jalankan dengan PERBARUI_GOLDEN=1)", nama, golden.display())];
+    };
+    let harapan: Vec<Value> = serde_json::from_str(&isi).expect("golden bukan JSON array yang valid");
+
+    if harapan.len() != records.len() {
+        return vec![format!("{}: jumlah sampel {} (harapan {})", nama, records.len(), harapan.len())];
+    }
+
+    let mut beda = Vec::new();
+    for (ke, (aktual, harapan)) in records.iter().zip(&harapan).enumerate() {
+        let (Some(aktual), Some(harapan)) = (aktual.as_object(), harapan.as_object()) else { continue };
+        for (field, nilai_harapan) in harapan {
+            let nilai_aktual = aktual.get(field).unwrap_or(&Value::Null);
+            if nilai_aktual != nilai_harapan {
+                beda.push(format!("{} sampel {} field {}: {} (harapan {})", nama, ke + 1, field, nilai_aktual, nilai_harapan));
+            }
+        }
+    }
+    beda
+}
+
+#[test]
+fn teks_laporan_sesuai_golden() {
+    let templates = vec![template_lab::template_default()];
+    let fixtures = daftar_fixture("txt");
+    assert!(!fixtures.is_empty(), "tidak ada fixture .txt");
+
+    let beda: Vec<String> = fixtures.iter()
+        .flat_map(|fixture| {
+            let teks = fs::read_to_string(fixture).expect("fixture tidak bisa dibaca");
+            let template = template_lab::detect(&templates, &teks);
+            let hasil = pdf_engine::parse_text(&teks, template).expect("parsing gagal");
+            bandingkan_golden(fixture, &hasil)
+        })
+        .collect();
+
+    assert!(beda.is_empty(), "hasil parsing berubah:\n{}", beda.join("\n"));
+}
+
+#[test]
+fn pdf_sintetis_sesuai_golden() {
+    let templates = vec![template_lab::template_default()];
+
+    let beda: Vec<String> = daftar_fixture("pdf").iter()
+        .flat_map(|fixture| {
+            let path = fixture.to_string_lossy().to_string();
+            let hasil = pdf_engine::parse_pdf(path, &templates).expect("PDF gagal dibaca");
+            bandingkan_golden(fixture, &hasil)
+        })
+        .collect();
+
+    assert!(beda.is_empty(), "hasil parsing PDF berubah:\n{}", beda.join("\n"));
+}
+
+#[test]
+fn template_bawaan_valid() {
+    template_lab::validate(&template_lab::template_default()).expect("templates/default.json tidak lolos validasi");
+}
+
+#[test]
+fn format_tanggal_dikenali() {
+    let harapan = NaiveDate::from_ymd_opt(2024, 3, 5);
+    for teks in ["2024-03-05", "05-03-2024", "5/3/2024", "05.03.2024", "5 Maret 2024", "Tgl. 5 Mar. 2024", "Selasa, 05-Mar-2024"] {
+        assert_eq!(tanggal::parse_tanggal(teks), harapan, "{}", teks);
+    }
+    assert_eq!(tanggal::parse_tanggal("31 Februari 2024"), None);
+    assert_eq!(tanggal::parse_waktu("7.30").map(|t| t.to_string()), Some("07:30:00".to_string()));
+}
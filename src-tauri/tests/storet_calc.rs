@@ -0,0 +1,115 @@
+// Skor STORET (storet_calc): tabel skor Kepmen LH 115/2003 berubah pada 10 sampel,
+// dan denda maksimum / minimum / rata-rata dinilai terpisah per parameter.
+
+use data_hidrologi_lib::models::baku_mutu::{BatasParameter, LimitType};
+use data_hidrologi_lib::models::kualitas_air::KualitasAirRecord;
+use data_hidrologi_lib::models::nilai_tersensor::KebijakanSensor;
+use data_hidrologi_lib::services::storet_calc::calculate_storet_collection;
+
+fn batas_max(parameter: &str, atas: f64) -> BatasParameter {
+    BatasParameter {
+        parameter: parameter.to_string(),
+        jenis: LimitType::Max,
+        batas_bawah: None,
+        batas_atas: Some(atas),
+        satuan: Some("mg/L".to_string()),
+        rujukan: None,
+    }
+}
+
+fn dataset_tss(nilai: &[f64]) -> Vec<KualitasAirRecord> {
+    nilai.iter()
+        .map(|&tss| KualitasAirRecord { tss: Some(tss), ..Default::default() })
+        .collect()
+}
+
+fn skor(nilai: &[f64], baku_mutu: &[BatasParameter]) -> (f64, String) {
+    let (skor, status, _) = calculate_storet_collection(&dataset_tss(nilai), baku_mutu, &[], KebijakanSensor::default());
+    (skor, status)
+}
+
+#[test]
+fn storet_tabel_skor_berganti_pada_sepuluh_sampel() {
+    let baku_mutu = [batas_max("tss", 50.0)];
+
+    // TSS (Fisika) melanggar maks, min & rata-rata: < 10 sampel -1/-1/-3, >= 10 sampel -2/-2/-6
+    assert_eq!(skor(&[100.0; 9], &baku_mutu), (-5.0, "Kelas B (Baik)".to_string()));
+    assert_eq!(skor(&[100.0; 10], &baku_mutu), (-10.0, "Kelas B (Baik)".to_string()));
+
+    // Kimia memakai baris tabelnya sendiri (-4 untuk maksimum pada >= 10 sampel)
+    let mut data = dataset_tss(&[1.0; 10]);
+    for (record, bod) in data.iter_mut().zip([2.0, 2.0, 2.0, 2.0, 2.0, 2.0, 2.0, 2.0, 2.0, 9.0]) {
+        record.bod = Some(bod);
+    }
+    let (total, _, rincian) = calculate_storet_collection(&data, &[batas_max("bod", 3.0)], &[], KebijakanSensor::default());
+    assert_eq!(total, -4.0);
+    assert_eq!(rincian[0].jumlah_sampel, 10);
+}
+
+#[test]
+fn storet_denda_maksimum_dan_rata_rata_dinilai_terpisah() {
+    let baku_mutu = [batas_max("tss", 50.0)];
+
+    // Hanya maksimum yang melampaui (rata-rata 40)
+    let (total, status, rincian) = calculate_storet_collection(
+        &dataset_tss(&[10.0, 10.0, 100.0]), &baku_mutu, &[], KebijakanSensor::default()
+    );
+    assert_eq!(total, -1.0);
+    assert_eq!(status, "Kelas B (Baik)");
+    assert!(rincian[0].melanggar_max && !rincian[0].melanggar_rata && !rincian[0].melanggar_min);
+
+    // Maksimum & rata-rata melampaui (rata-rata 70), minimum tetap memenuhi
+    let (total, _, rincian) = calculate_storet_collection(
+        &dataset_tss(&[10.0, 100.0, 100.0]), &baku_mutu, &[], KebijakanSensor::default()
+    );
+    assert_eq!(total, -4.0);
+    assert!(rincian[0].melanggar_max && rincian[0].melanggar_rata && !rincian[0].melanggar_min);
+
+    // Seluruh nilai memenuhi
+    assert_eq!(skor(&[10.0, 20.0], &baku_mutu), (0.0, "Kelas A (Baik Sekali)".to_string()));
+}
+
+#[test]
+fn storet_kurang_dari_dua_sampel_tidak_dinilai() {
+    let baku_mutu = [batas_max("tss", 50.0)];
+
+    for nilai in [&[][..], &[100.0][..]] {
+        let (total, status, rincian) = calculate_storet_collection(
+            &dataset_tss(nilai), &baku_mutu, &[], KebijakanSensor::default()
+        );
+        assert_eq!(total, 0.0);
+        assert_eq!(status, "Data Kurang (Min. 2 Sampel)");
+        assert!(rincian.is_empty());
+    }
+}
+
+#[test]
+fn storet_rincian_kategori_dan_nilai_tersensor() {
+    use data_hidrologi_lib::models::hasil_perhitungan::Kategori;
+    use data_hidrologi_lib::models::nilai_tersensor::{Kualifier, NilaiTersensor};
+
+    let mut data = dataset_tss(&[20.0, 40.0, 60.0]);
+    for (record, (bod, coliform)) in data.iter_mut().zip([(2.0, 800.0), (4.0, 1200.0), (6.0, 400.0)]) {
+        record.bod = Some(bod);
+        record.total_coliform = Some(coliform);
+    }
+    data[0].tersensor.push(NilaiTersensor { parameter: "bod".to_string(), kualifier: Kualifier::KurangDari, batas_deteksi: 2.0 });
+    let baku_mutu = [batas_max("tss", 50.0), batas_max("bod", 3.0), batas_max("total_coliform", 1000.0)];
+
+    let (_, _, rincian) = calculate_storet_collection(&data, &baku_mutu, &[], KebijakanSensor::Lod);
+    let kategori: Vec<(&str, Kategori)> = rincian.iter().map(|r| (r.parameter.as_str(), r.kategori)).collect();
+    assert_eq!(kategori, [("tss", Kategori::Fisika), ("bod", Kategori::Kimia), ("total_coliform", Kategori::Biologi)]);
+
+    let bod = &rincian[1];
+    assert_eq!((bod.jumlah_sampel, bod.jumlah_tersensor), (3, 1));
+    assert_eq!((bod.min, bod.max, bod.rata), (2.0, 6.0, 4.0));
+    assert_eq!((bod.batas_bawah, bod.batas_atas), (None, Some(3.0)));
+    // Kimia < 10 sampel: maks -2, rata-rata -4; minimum 2 memenuhi
+    assert!(bod.melanggar_max && bod.melanggar_rata && !bod.melanggar_min);
+    assert_eq!(bod.skor, -6.0);
+    assert_eq!(rincian[0].jumlah_tersensor, 0);
+
+    // Abaikan: nilai tersensor tidak ikut statistik maupun hitungan tersensor
+    let (_, _, rincian) = calculate_storet_collection(&data, &baku_mutu, &[], KebijakanSensor::Abaikan);
+    assert_eq!((rincian[1].jumlah_sampel, rincian[1].jumlah_tersensor, rincian[1].min), (2, 0, 4.0));
+}
@@ -0,0 +1,62 @@
+// Export Excel (xlsx_service): file dibaca ulang dengan calamine untuk memastikan
+// sel bertipe angka/tanggal, judul kolom bersatuan, dan isi sheet ringkasan per pos.
+
+use calamine::{open_workbook, Data, Reader, Xlsx};
+use chrono::NaiveDate;
+use data_hidrologi_lib::models::ekspor_xlsx::OpsiEksporXlsx;
+use data_hidrologi_lib::models::kualitas_air::KualitasAirRecord;
+use data_hidrologi_lib::services::xlsx_service::{self, AcuanBakuMutu};
+
+fn sampel(id: i64, nama_pos: &str, tanggal: (i32, u32, u32), tss: f64, nilai_ip: f64, status_ip: &str) -> KualitasAirRecord {
+    KualitasAirRecord {
+        id: Some(id),
+        nama_pos: Some(nama_pos.to_string()),
+        tanggal_sampling: NaiveDate::from_ymd_opt(tanggal.0, tanggal.1, tanggal.2),
+        ph: Some(7.2),
+        tss: Some(tss),
+        bod: Some(2.5),
+        nilai_ip: Some(nilai_ip),
+        status_ip: Some(status_ip.to_string()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn export_xlsx_sheet_data_dan_ringkasan() {
+    let data = vec![
+        sampel(1, "Pos Hulu", (2024, 3, 4), 20.0, 0.8, "Memenuhi Baku Mutu"),
+        sampel(2, "Pos Hulu", (2024, 6, 10), 180.0, 2.6, "Cemar Ringan"),
+        sampel(3, "Pos Hilir", (2024, 6, 11), 75.0, 1.4, "Cemar Ringan"),
+    ];
+    let opsi = OpsiEksporXlsx {
+        kolom: ["id", "tanggal_sampling", "nama_pos", "ph", "tss", "status_ip"].map(String::from).to_vec(),
+        ..Default::default()
+    };
+    let path = std::env::temp_dir().join(format!("ekspor_kualitas_air_test_{}.xlsx", std::process::id()));
+    let path = path.to_string_lossy();
+
+    xlsx_service::export_to_xlsx(&data, &opsi, &AcuanBakuMutu::default(), &path).expect("export gagal");
+
+    let mut workbook: Xlsx<_> = open_workbook(path.as_ref()).expect("file tidak terbaca");
+    assert_eq!(workbook.sheet_names(), ["Data Kualitas Air", "Ringkasan per Pos"]);
+
+    // Sheet data: judul bersatuan, nilai parameter & tanggal bertipe
+    let data_sheet = workbook.worksheet_range("Data Kualitas Air").unwrap();
+    assert_eq!(data_sheet.get((0, 4)), Some(&Data::String("TSS (Zat Padat Tersuspensi) [mg/L]".to_string())));
+    assert_eq!(data_sheet.get((2, 4)), Some(&Data::Float(180.0)));
+    assert_eq!(data_sheet.get((2, 0)), Some(&Data::Float(2.0)));
+    assert!(matches!(data_sheet.get((2, 1)), Some(Data::DateTime(_))));
+    assert_eq!(data_sheet.get((2, 5)), Some(&Data::String("Cemar Ringan".to_string())));
+
+    // Ringkasan: satu baris per pos (urut nama), jumlah sampel & IP terakhir
+    let ringkasan = workbook.worksheet_range("Ringkasan per Pos").unwrap();
+    assert_eq!(ringkasan.get((1, 0)), Some(&Data::String("Pos Hilir".to_string())));
+    assert_eq!(ringkasan.get((2, 0)), Some(&Data::String("Pos Hulu".to_string())));
+    assert_eq!(ringkasan.get((2, 3)), Some(&Data::Float(2.0)));
+    assert_eq!(ringkasan.get((2, 9)), Some(&Data::Float(2.6)));
+    assert_eq!(ringkasan.get((2, 10)), Some(&Data::String("Cemar Ringan".to_string())));
+    // Pos Hilir hanya satu sampel: STORET tidak bisa dihitung, skor dibiarkan kosong
+    assert_eq!(ringkasan.get((1, 16)), Some(&Data::Empty));
+
+    std::fs::remove_file(path.as_ref()).ok();
+}
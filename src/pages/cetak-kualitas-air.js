@@ -58,7 +58,7 @@ export function populatePrintTemplate(record) {
     setVal('lap_kab_prov', kabProv);
     
     setVal('lap_tanggal', formatDate(record.tanggal_sampling));
-    setVal('lap_waktu', record.waktu_sampling);
+    setVal('lap_waktu', record.waktu_sampling ? record.waktu_sampling.slice(0, 5) : null);
     
     setVal('lap_pelaksana', record.pelaksana);
     setVal('lap_laboratorium', record.laboratorium);
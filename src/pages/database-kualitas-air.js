@@ -6,6 +6,15 @@ const { invoke } = window.__TAURI__.core;
 
 // Import modul cetak yang baru
 import { initPrintSystem, populatePrintTemplate, executePrint } from './cetak-kualitas-air.js';
+import { initImporTabel } from './impor-tabel.js';
+
+// Status halaman aktif (filter & urutan dijalankan di backend)
+let halaman = 1;
+let perHalaman = 50;
+let jumlahHalaman = 1;
+
+// Record yang sedang ditampilkan di modal pratinjau (untuk laporan PDF backend)
+let recordLaporan = null;
 
 // ============================================
 // MAIN INITIALIZATION
@@ -28,16 +37,20 @@ function setupBasicEventListeners() {
     const btnRefresh = document.getElementById('btnRefresh');
     if (btnRefresh) btnRefresh.addEventListener('click', loadData);
     
-    // 2. Export All (Bulk CSV)
+    // 2. Export Excel / CSV (dialog pilihan kolom & format)
     const btnExportAll = document.getElementById('btnExportAll');
     if (btnExportAll) {
         btnExportAll.addEventListener('click', async (e) => {
             e.stopPropagation();
-            await handleBulkExport();
+            await openExportDialog();
         });
     }
+    setupExportListeners();
+
+    // 3. Import CSV / Excel (muat ulang daftar setelah tersimpan)
+    initImporTabel(loadData);
     
-    // 3. Navigation Buttons
+    // 4. Navigation Buttons
     const navToInput = () => {
         const navKualitasAir = document.getElementById('nav-kualitas-air');
         if (navKualitasAir) navKualitasAir.click();
@@ -51,15 +64,18 @@ function setupBasicEventListeners() {
     
     const btnRetry = document.getElementById('btnRetry');
     if (btnRetry) btnRetry.addEventListener('click', loadData);
+
+    // 5. Filter & Paginasi
+    setupFilterListeners();
     
-    // 4. Modal Controls (Close)
+    // 6. Modal Controls (Close)
     const btnCloseModal = document.getElementById('btnCloseModal');
     const btnCloseModal2 = document.getElementById('btnCloseModal2');
     
     if (btnCloseModal) btnCloseModal.addEventListener('click', hideModal);
     if (btnCloseModal2) btnCloseModal2.addEventListener('click', hideModal);
     
-    // 5. PRINT PDF BUTTON (Action di dalam Modal)
+    // 7. PRINT PDF BUTTON (Action di dalam Modal)
     const btnPrintPDF = document.getElementById('btnPrintPDF');
     if (btnPrintPDF) {
         btnPrintPDF.addEventListener('click', () => {
@@ -67,6 +83,87 @@ function setupBasicEventListeners() {
             executePrint(); // Panggil fungsi dari modul cetak
         });
     }
+
+    // 8. LAPORAN PDF (disusun di backend, disimpan ke lokasi pilihan user)
+    const btnLaporanPdf = document.getElementById('btnLaporanPdf');
+    if (btnLaporanPdf) btnLaporanPdf.addEventListener('click', simpanLaporanPdf);
+}
+
+function setupFilterListeners() {
+    const terapkan = () => { halaman = 1; loadData(); };
+
+    const btnTerapkan = document.getElementById('btnTerapkanFilter');
+    if (btnTerapkan) btnTerapkan.addEventListener('click', terapkan);
+
+    const filterCari = document.getElementById('filterCari');
+    if (filterCari) {
+        filterCari.addEventListener('keydown', (e) => {
+            if (e.key === 'Enter') terapkan();
+        });
+    }
+
+    // Dropdown langsung diterapkan begitu diubah
+    ['filterStatusIp', 'filterKelasStoret', 'filterUrut'].forEach(id => {
+        const el = document.getElementById(id);
+        if (el) el.addEventListener('change', terapkan);
+    });
+
+    const btnReset = document.getElementById('btnResetFilter');
+    if (btnReset) {
+        btnReset.addEventListener('click', () => {
+            document.querySelectorAll('.filter-controls input').forEach(el => { el.value = ''; });
+            document.querySelectorAll('.filter-controls select').forEach(el => { el.selectedIndex = 0; });
+            terapkan();
+        });
+    }
+
+    const btnSebelum = document.getElementById('btnHalamanSebelum');
+    if (btnSebelum) {
+        btnSebelum.addEventListener('click', () => {
+            if (halaman > 1) { halaman--; loadData(); }
+        });
+    }
+
+    const btnBerikut = document.getElementById('btnHalamanBerikut');
+    if (btnBerikut) {
+        btnBerikut.addEventListener('click', () => {
+            if (halaman < jumlahHalaman) { halaman++; loadData(); }
+        });
+    }
+
+    const selPerHalaman = document.getElementById('perHalaman');
+    if (selPerHalaman) {
+        selPerHalaman.addEventListener('change', () => {
+            perHalaman = parseInt(selPerHalaman.value, 10) || 50;
+            terapkan();
+        });
+    }
+}
+
+// Nilai form filter -> parameter command query_kualitas_air
+function bacaFilter() {
+    const nilai = (id) => {
+        const el = document.getElementById(id);
+        const v = el ? el.value.trim() : '';
+        return v === '' ? null : v;
+    };
+    const [urut, arah] = (nilai('filterUrut') || 'tanggal_sampling:desc').split(':');
+
+    return {
+        filter: {
+            cari: nilai('filterCari'),
+            das: nilai('filterDas'),
+            kabupaten: nilai('filterKabupaten'),
+            status_ip: nilai('filterStatusIp'),
+            kelas_storet: nilai('filterKelasStoret'),
+            tanggal_awal: nilai('filterTanggalAwal'),   // input date sudah berformat YYYY-MM-DD
+            tanggal_akhir: nilai('filterTanggalAkhir'),
+        },
+        urut,
+        arah,
+        halaman,
+        per_halaman: perHalaman,
+    };
 }
 
 // ============================================
@@ -76,21 +173,32 @@ function setupBasicEventListeners() {
 async function loadData() {
     try {
         showLoading();
-        const rawData = await invoke('get_all_kualitas_air');
-        const transformedData = transformData(rawData);
-        renderCards(transformedData);
+        const hasil = await invoke('query_kualitas_air', { query: bacaFilter() });
+
+        // Halaman aktif bisa melewati batas setelah data dihapus / filter berubah
+        jumlahHalaman = Math.max(1, Math.ceil(hasil.total / hasil.per_halaman));
+        if (hasil.data.length === 0 && halaman > 1) {
+            halaman = jumlahHalaman;
+            return loadData();
+        }
+
+        const transformedData = transformData(hasil.data);
+        renderCards(transformedData, hasil.total);
+        updateStats(hasil.total, hasil.per_status_ip);
+        updatePager();
     } catch (error) {
         console.error("❌ Error loading data:", error);
         showErrorState(error);
     }
 }
 
-function renderCards(data) {
+function renderCards(data, total) {
     hideLoading();
     
     const recordCount = document.getElementById('recordCount');
-    if (recordCount) recordCount.textContent = data.length;
+    if (recordCount) recordCount.textContent = total;
     
+    document.getElementById('errorState').style.display = 'none';
     if (!data || data.length === 0) {
         showEmptyState();
         return;
@@ -108,8 +216,16 @@ function renderCards(data) {
             cardsGrid.appendChild(createCardElement(record, index));
         });
     }
-    
-    updateStats(data);
+}
+
+function updatePager() {
+    const pager = document.getElementById('pager');
+    if (!pager) return;
+
+    pager.style.display = jumlahHalaman > 1 ? 'flex' : 'none';
+    document.getElementById('infoHalaman').textContent = `Halaman ${halaman} dari ${jumlahHalaman}`;
+    document.getElementById('btnHalamanSebelum').disabled = halaman <= 1;
+    document.getElementById('btnHalamanBerikut').disabled = halaman >= jumlahHalaman;
 }
 
 function createCardElement(record, index) {
@@ -184,6 +300,8 @@ function createCardElement(record, index) {
 function openPrintPreview(record) {
     const modal = document.getElementById('detailModal');
     
+    recordLaporan = record;
+
     // Panggil fungsi dari modul cetak untuk mengisi data
     populatePrintTemplate(record);
 
@@ -287,10 +405,124 @@ function hideModal() {
     if (modal) modal.style.display = 'none';
 }
 
-async function handleBulkExport() {
+// ============================================
+// EXPORT EXCEL / CSV
+// ============================================
+
+// Pilihan export terakhir disimpan agar tidak perlu diatur ulang setiap kali
+const KUNCI_OPSI_EKSPOR = 'opsiEksporCsv';
+
+function setupExportListeners() {
+    const tutup = () => { document.getElementById('exportModal').style.display = 'none'; };
+    ['btnCloseExport', 'btnBatalExport'].forEach(id => {
+        const el = document.getElementById(id);
+        if (el) el.addEventListener('click', tutup);
+    });
+
+    const centang = (nilai) => {
+        document.querySelectorAll('#exportKolom input[type="checkbox"]').forEach(cb => { cb.checked = nilai; });
+    };
+    const btnSemua = document.getElementById('btnKolomSemua');
+    if (btnSemua) btnSemua.addEventListener('click', () => centang(true));
+    const btnKosong = document.getElementById('btnKolomKosong');
+    if (btnKosong) btnKosong.addEventListener('click', () => centang(false));
+
+    // Tombol naik / turun untuk mengatur urutan kolom
+    const daftar = document.getElementById('exportKolom');
+    if (daftar) {
+        daftar.addEventListener('click', (e) => {
+            const btn = e.target.closest('button');
+            if (!btn) return;
+            const li = btn.closest('li');
+            if (btn.dataset.arah === 'naik' && li.previousElementSibling) {
+                daftar.insertBefore(li, li.previousElementSibling);
+            } else if (btn.dataset.arah === 'turun' && li.nextElementSibling) {
+                daftar.insertBefore(li.nextElementSibling, li);
+            }
+        });
+    }
+
+    // Pemisah kolom & desimal hanya berlaku untuk CSV
+    const jenis = document.getElementById('exportJenis');
+    if (jenis) jenis.addEventListener('change', tampilOpsiCsv);
+
+    const btnMulai = document.getElementById('btnMulaiExport');
+    if (btnMulai) btnMulai.addEventListener('click', handleExport);
+}
+
+function tampilOpsiCsv() {
+    const csv = document.getElementById('exportJenis').value === 'csv';
+    document.getElementById('exportOpsiCsv').style.display = csv ? '' : 'none';
+}
+
+async function openExportDialog() {
+    try {
+        const semuaKolom = await invoke('get_kolom_ekspor');
+        const tersimpan = JSON.parse(localStorage.getItem(KUNCI_OPSI_EKSPOR) || 'null');
+
+        // Kolom tersimpan ditampilkan dulu sesuai urutannya, sisanya menyusul tanpa centang
+        let urutan = semuaKolom;
+        let dipilih = new Set(semuaKolom.map(k => k.kunci));
+        if (tersimpan && Array.isArray(tersimpan.kolom) && tersimpan.kolom.length > 0) {
+            const perKunci = new Map(semuaKolom.map(k => [k.kunci, k]));
+            const awal = tersimpan.kolom.filter(k => perKunci.has(k)).map(k => perKunci.get(k));
+            urutan = awal.concat(semuaKolom.filter(k => !tersimpan.kolom.includes(k.kunci)));
+            dipilih = new Set(tersimpan.kolom);
+        }
+        if (tersimpan) {
+            document.getElementById('exportJenis').value = tersimpan.jenis || 'xlsx';
+            document.getElementById('exportPemisah').value = tersimpan.pemisah || 'titik_koma';
+            document.getElementById('exportDesimal').value = tersimpan.desimal || 'koma';
+        }
+
+        const daftar = document.getElementById('exportKolom');
+        daftar.innerHTML = '';
+        urutan.forEach(({ kunci, judul }) => {
+            const li = document.createElement('li');
+            li.dataset.kunci = kunci;
+            li.innerHTML = `
+                <input type="checkbox" id="kolom_${kunci}" ${dipilih.has(kunci) ? 'checked' : ''}>
+                <label for="kolom_${kunci}"></label>
+                <button data-arah="naik" title="Naik"><i class="fas fa-arrow-up"></i></button>
+                <button data-arah="turun" title="Turun"><i class="fas fa-arrow-down"></i></button>
+            `;
+            li.querySelector('label').textContent = judul;
+            daftar.appendChild(li);
+        });
+
+        tampilOpsiCsv();
+        document.getElementById('exportModal').style.display = 'flex';
+    } catch (error) {
+        alert("❌ Gagal menyiapkan export: " + error);
+    }
+}
+
+async function handleExport() {
+    const kolom = [...document.querySelectorAll('#exportKolom li')]
+        .filter(li => li.querySelector('input[type="checkbox"]').checked)
+        .map(li => li.dataset.kunci);
+    if (kolom.length === 0) {
+        alert("⚠️ Pilih minimal satu kolom untuk diexport.");
+        return;
+    }
+
+    const { filter, urut, arah } = bacaFilter();
+    const jenis = document.getElementById('exportJenis').value;
+    const opsi = {
+        filter,
+        urut,
+        arah,
+        kolom,
+        pemisah: document.getElementById('exportPemisah').value,
+        desimal: document.getElementById('exportDesimal').value,
+    };
+
     try {
         document.body.style.cursor = 'wait';
-        const result = await invoke('export_kualitas_air_csv');
+        const command = jenis === 'xlsx' ? 'export_kualitas_air_xlsx' : 'export_kualitas_air_csv';
+        const result = await invoke(command, { opsi });
+        localStorage.setItem(KUNCI_OPSI_EKSPOR, JSON.stringify({ jenis, kolom, pemisah: opsi.pemisah, desimal: opsi.desimal }));
+        document.getElementById('exportModal').style.display = 'none';
         alert("✅ Export Berhasil:\n" + result);
     } catch (error) {
         if (!String(error).includes("dibatalkan")) alert("❌ Gagal Export: " + error);
@@ -299,17 +531,25 @@ async function handleBulkExport() {
     }
 }
 
+async function simpanLaporanPdf() {
+    if (!recordLaporan || !recordLaporan.id) return;
+    try {
+        document.body.style.cursor = 'wait';
+        const result = await invoke('export_laporan_pdf', { id: recordLaporan.id });
+        alert("✅ " + result);
+    } catch (error) {
+        if (!String(error).includes("dibatalkan")) alert("❌ Gagal Membuat Laporan: " + error);
+    } finally {
+        document.body.style.cursor = 'default';
+    }
+}
+
 async function deleteRecord(id, cardElement) {
     try {
         await invoke('delete_kualitas_air', { id });
         cardElement.style.opacity = '0';
-        setTimeout(() => {
-            cardElement.remove();
-            const grid = document.getElementById('cardsGrid');
-            if (grid && grid.children.length === 0) showEmptyState();
-            const counter = document.getElementById('recordCount');
-            if (counter) counter.textContent = grid ? grid.children.length : 0;
-        }, 300);
+        // Muat ulang halaman agar total, statistik & paginasi tetap sesuai
+        setTimeout(loadData, 300);
         alert("✅ Data berhasil dihapus.");
     } catch (error) {
         alert("❌ Gagal menghapus: " + error);
@@ -321,6 +561,8 @@ function showLoading() {
     if (lc) lc.style.display = 'block';
     const grid = document.getElementById('cardsGrid');
     if (grid) grid.style.display = 'none';
+    const empty = document.getElementById('emptyState');
+    if (empty) empty.style.display = 'none';
 }
 
 function hideLoading() {
@@ -342,15 +584,15 @@ function showErrorState(msg) {
     }
 }
 
-function updateStats(data) {
-    if (!data) return;
-    const counts = { total: data.length, good: 0, light: 0, medium: 0, heavy: 0 };
-    data.forEach(r => {
-        const s = (r.status_ip || r.statusIp || '').toLowerCase(); // Cek kedua key juga di sini
-        if (s.includes('memenuhi')) counts.good++;
-        else if (s.includes('ringan')) counts.light++;
-        else if (s.includes('sedang')) counts.medium++;
-        else if (s.includes('berat')) counts.heavy++;
+// Statistik dari backend: seluruh data hasil filter, bukan hanya halaman ini
+function updateStats(total, perStatus) {
+    const counts = { total, good: 0, light: 0, medium: 0, heavy: 0 };
+    (perStatus || []).forEach(({ status, jumlah }) => {
+        const s = (status || '').toLowerCase();
+        if (s.includes('memenuhi')) counts.good += jumlah;
+        else if (s.includes('ringan')) counts.light += jumlah;
+        else if (s.includes('sedang')) counts.medium += jumlah;
+        else if (s.includes('berat')) counts.heavy += jumlah;
     });
     const stats = document.querySelectorAll('.stat-value');
     if (stats.length >= 5) {
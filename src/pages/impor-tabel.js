@@ -0,0 +1,287 @@
+// Import Data Kualitas Air dari CSV / Excel
+// Alur: pilih file -> atur sheet/baris judul -> petakan kolom -> pratinjau -> simpan (satu transaksi)
+
+const { invoke } = window.__TAURI__.core;
+
+let pathFile = null;
+let fileInfo = null;       // Hasil baca_file_impor (judul, contoh, saran, lembar)
+let daftarField = [];      // Field tujuan dari backend
+let daftarPemetaan = [];   // Pemetaan tersimpan
+let setelahSimpan = null;  // Callback untuk memuat ulang daftar data
+
+export function initImporTabel(onSelesai) {
+    setelahSimpan = onSelesai;
+
+    const tutup = () => { document.getElementById('importModal').style.display = 'none'; };
+    ['btnCloseImport', 'btnBatalImpor'].forEach(id => {
+        const el = document.getElementById(id);
+        if (el) el.addEventListener('click', tutup);
+    });
+
+    const on = (id, event, handler) => {
+        const el = document.getElementById(id);
+        if (el) el.addEventListener(event, handler);
+    };
+    on('btnImportTabel', 'click', bukaDialog);
+    on('btnPilihFileImpor', 'click', pilihFile);
+    on('importPemetaan', 'change', terapkanPemetaanTersimpan);
+    on('btnSimpanPemetaan', 'click', simpanPemetaan);
+    on('btnHapusPemetaan', 'click', hapusPemetaan);
+    on('btnPratinjauImpor', 'click', pratinjau);
+    on('btnSimpanImpor', 'click', simpanImpor);
+
+    // Pengaturan baca berubah -> baca ulang judul kolom
+    ['importLembar', 'importBarisHeader', 'importPemisah'].forEach(id => on(id, 'change', () => bacaFile()));
+    on('importDesimal', 'change', resetPratinjau);
+}
+
+async function bukaDialog() {
+    try {
+        daftarField = await invoke('get_field_impor');
+        await muatDaftarPemetaan();
+        renderPemetaan({});
+        resetPratinjau();
+        document.getElementById('importModal').style.display = 'flex';
+    } catch (error) {
+        alert("❌ Gagal menyiapkan import: " + error);
+    }
+}
+
+async function muatDaftarPemetaan(terpilih = '') {
+    const { pemetaan, peringatan } = await invoke('get_all_pemetaan_impor');
+    daftarPemetaan = pemetaan;
+    if (peringatan.length > 0) alert("⚠️ Sebagian pemetaan tersimpan tidak bisa dibaca:\n" + peringatan.join("\n"));
+    const select = document.getElementById('importPemetaan');
+    select.innerHTML = '<option value="">(Baru)</option>';
+    daftarPemetaan.forEach(p => {
+        const opt = document.createElement('option');
+        opt.value = p.nama;
+        opt.textContent = p.nama;
+        select.appendChild(opt);
+    });
+    select.value = terpilih;
+}
+
+async function pilihFile() {
+    try {
+        pathFile = await invoke('pilih_file_impor');
+        document.getElementById('importPath').textContent = pathFile;
+        document.getElementById('importLembar').innerHTML = '';
+        await bacaFile(true);
+    } catch (error) {
+        if (!String(error).includes("dibatalkan")) alert("❌ Gagal membuka file: " + error);
+    }
+}
+
+// Pengaturan form -> PemetaanImpor untuk backend
+function bacaPemetaan() {
+    const kolom = {};
+    document.querySelectorAll('#importPemetaanBody select').forEach(sel => {
+        if (sel.value) kolom[sel.dataset.field] = sel.value;
+    });
+    return {
+        nama: document.getElementById('importPemetaan').value || 'Baru',
+        lembar: document.getElementById('importLembar').value || null,
+        baris_header: parseInt(document.getElementById('importBarisHeader').value, 10) || 1,
+        pemisah: document.getElementById('importPemisah').value || null,
+        desimal: document.getElementById('importDesimal').value,
+        kolom,
+    };
+}
+
+// Baca judul kolom & contoh data. Pemetaan yang sudah dipilih dipertahankan jika judulnya masih ada;
+// file yang baru dipilih memakai saran dari backend.
+async function bacaFile(fileBaru = false) {
+    if (!pathFile) return;
+    const pemetaan = bacaPemetaan();
+    try {
+        fileInfo = await invoke('baca_file_impor', { path: pathFile, pemetaan });
+
+        const lembar = document.getElementById('importLembar');
+        const aktif = lembar.value;
+        lembar.innerHTML = '';
+        lembar.disabled = fileInfo.lembar.length === 0;
+        fileInfo.lembar.forEach(nama => {
+            const opt = document.createElement('option');
+            opt.value = nama;
+            opt.textContent = nama;
+            lembar.appendChild(opt);
+        });
+        if (aktif) lembar.value = aktif;
+
+        const pakaiSaran = fileBaru && !document.getElementById('importPemetaan').value;
+        renderPemetaan(pakaiSaran ? fileInfo.saran : pemetaan.kolom);
+        resetPratinjau();
+    } catch (error) {
+        fileInfo = null;
+        renderPemetaan({});
+        alert("❌ Gagal membaca file: " + error);
+    }
+}
+
+function renderPemetaan(kolom) {
+    const tbody = document.getElementById('importPemetaanBody');
+    tbody.innerHTML = '';
+    const judul = fileInfo ? fileInfo.judul : [];
+
+    daftarField.forEach(({ kunci, judul: label }) => {
+        const tr = document.createElement('tr');
+        const tdLabel = document.createElement('td');
+        tdLabel.textContent = label;
+
+        const select = document.createElement('select');
+        select.dataset.field = kunci;
+        select.innerHTML = '<option value="">— tidak diimpor —</option>';
+        judul.forEach(j => {
+            const opt = document.createElement('option');
+            opt.value = j;
+            opt.textContent = j;
+            select.appendChild(opt);
+        });
+        if (kolom[kunci] && judul.includes(kolom[kunci])) select.value = kolom[kunci];
+
+        const tdContoh = document.createElement('td');
+        tdContoh.className = 'contoh';
+        const tampilContoh = () => {
+            const idx = judul.indexOf(select.value);
+            tdContoh.textContent = idx < 0 || !fileInfo ? '' :
+                fileInfo.contoh.map(baris => baris[idx] || '').filter(Boolean).slice(0, 3).join(' | ');
+        };
+        select.addEventListener('change', () => { tampilContoh(); resetPratinjau(); });
+        tampilContoh();
+
+        const tdSelect = document.createElement('td');
+        tdSelect.appendChild(select);
+        tr.append(tdLabel, tdSelect, tdContoh);
+        tbody.appendChild(tr);
+    });
+}
+
+async function terapkanPemetaanTersimpan() {
+    const nama = document.getElementById('importPemetaan').value;
+    const pemetaan = daftarPemetaan.find(p => p.nama === nama);
+    if (!pemetaan) return;
+
+    document.getElementById('importBarisHeader').value = pemetaan.baris_header;
+    document.getElementById('importPemisah').value = pemetaan.pemisah || '';
+    document.getElementById('importDesimal').value = pemetaan.desimal;
+    if (pemetaan.lembar) {
+        const lembar = document.getElementById('importLembar');
+        if (![...lembar.options].some(o => o.value === pemetaan.lembar)) {
+            lembar.add(new Option(pemetaan.lembar, pemetaan.lembar));
+        }
+        lembar.value = pemetaan.lembar;
+    }
+
+    // Baca ulang file dengan pengaturan pemetaan, lalu isi pilihan kolomnya
+    await bacaFile();
+    renderPemetaan(pemetaan.kolom);
+}
+
+async function simpanPemetaan() {
+    const pemetaan = bacaPemetaan();
+    const nama = prompt("Nama pemetaan (misal format spreadsheet BBWS 2019):", document.getElementById('importPemetaan').value);
+    if (!nama || !nama.trim()) return;
+    pemetaan.nama = nama.trim();
+
+    try {
+        await invoke('save_pemetaan_impor', { data: pemetaan });
+        await muatDaftarPemetaan(pemetaan.nama);
+        alert("✅ Pemetaan tersimpan.");
+    } catch (error) {
+        alert("❌ Gagal menyimpan pemetaan: " + error);
+    }
+}
+
+async function hapusPemetaan() {
+    const nama = document.getElementById('importPemetaan').value;
+    if (!nama || !confirm(`Hapus pemetaan '${nama}'?`)) return;
+    try {
+        await invoke('delete_pemetaan_impor', { nama });
+        await muatDaftarPemetaan();
+    } catch (error) {
+        alert("❌ Gagal menghapus pemetaan: " + error);
+    }
+}
+
+function resetPratinjau() {
+    document.getElementById('importPratinjau').style.display = 'none';
+    document.getElementById('btnSimpanImpor').disabled = true;
+}
+
+async function pratinjau() {
+    if (!pathFile) {
+        alert("⚠️ Pilih file terlebih dahulu.");
+        return;
+    }
+    try {
+        document.body.style.cursor = 'wait';
+        const hasil = await invoke('pratinjau_impor', { path: pathFile, pemetaan: bacaPemetaan() });
+        renderPratinjau(hasil);
+        document.getElementById('btnSimpanImpor').disabled = hasil.jumlah_valid === 0;
+    } catch (error) {
+        resetPratinjau();
+        alert("❌ Pratinjau gagal: " + error);
+    } finally {
+        document.body.style.cursor = 'default';
+    }
+}
+
+function renderPratinjau(hasil) {
+    const duplikat = hasil.baris.filter(b => b.duplikat).length;
+    document.getElementById('importRingkasan').textContent =
+        `${hasil.jumlah_valid} baris valid, ${hasil.jumlah_gagal} baris error, ${duplikat} duplikat`;
+
+    const peringatan = document.getElementById('importPeringatan');
+    peringatan.innerHTML = '';
+    hasil.peringatan.forEach(p => {
+        const li = document.createElement('li');
+        li.textContent = p;
+        peringatan.appendChild(li);
+    });
+
+    const tbody = document.getElementById('importPratinjauBody');
+    tbody.innerHTML = '';
+    hasil.baris.forEach(b => {
+        const tr = document.createElement('tr');
+        const [status, kelas] = b.record
+            ? (b.duplikat ? ['Duplikat', 'import-status-dup'] : ['OK', 'import-status-ok'])
+            : ['Error', 'import-status-error'];
+        const sel = [
+            String(b.baris),
+            status,
+            b.record ? (b.record.nama_pos || '-') : '-',
+            b.record ? (b.record.tanggal_sampling || '-') : '-',
+            b.error.concat(b.peringatan).join('; '),
+        ];
+        sel.forEach((isi, idx) => {
+            const td = document.createElement('td');
+            td.textContent = isi;
+            if (idx === 1) td.className = kelas;
+            tr.appendChild(td);
+        });
+        tbody.appendChild(tr);
+    });
+
+    document.getElementById('importPratinjau').style.display = 'block';
+}
+
+async function simpanImpor() {
+    const lewatiDuplikat = document.getElementById('importLewatiDuplikat').checked;
+    try {
+        document.body.style.cursor = 'wait';
+        const hasil = await invoke('simpan_impor', {
+            path: pathFile,
+            pemetaan: bacaPemetaan(),
+            lewatiDuplikat,
+        });
+        const dilewati = hasil.baris.length - hasil.ids.length;
+        alert(`✅ ${hasil.ids.length} data berhasil diimport.` + (dilewati > 0 ? `\n${dilewati} baris dilewati (error / duplikat).` : ''));
+        document.getElementById('importModal').style.display = 'none';
+        if (setelahSimpan) setelahSimpan();
+    } catch (error) {
+        alert("❌ Import gagal: " + error);
+    } finally {
+        document.body.style.cursor = 'default';
+    }
+}
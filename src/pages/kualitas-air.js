@@ -27,18 +27,41 @@ async function handlePdfUpload() {
         console.log("Meminta Rust membuka dialog file...");
         document.body.style.cursor = 'wait';
 
-        // 1. Panggil Rust untuk baca PDF
-        const data = await invoke('import_pdf');
+        // 1. Panggil Rust untuk baca PDF (record + laporan ekstraksi, satu per kolom sampel)
+        const hasil = await invoke('import_pdf');
 
         document.body.style.cursor = 'default';
 
+        // PDF multi-sampel: pilih sampel yang diisi ke form
+        let pilihan = 0;
+        if (hasil.length > 1) {
+            const daftar = hasil
+                .map((h, i) => `${i + 1}. ${h.record.nama_pos || '-'} (${h.record.tanggal_sampling || '-'})`)
+                .join("\n");
+            const jawab = prompt(`PDF berisi ${hasil.length} sampel. Pilih nomor sampel yang diisi ke form:\n${daftar}`, "1");
+            if (jawab === null) return;
+            pilihan = Math.min(Math.max(parseInt(jawab, 10) || 1, 1), hasil.length) - 1;
+        }
+        const { record: data, laporan } = hasil[pilihan];
+
         if (data) {
             console.log("📄 Data Diterima dari Rust:", data);
+            console.table(laporan.field);
             
             // 2. Isi Form Otomatis
             fillForm(data);
 
-            alert("✅ Data berhasil diekstrak dan dimasukkan ke form!");
+            // 3. Daftar nilai yang perlu dicek manual
+            const perluCek = laporan.field
+                .filter(f => f.keyakinan < 0.9)
+                .map(f => `- ${f.field} (baris ${f.nomor_baris}): ${f.catatan || ''}`);
+            const tidakTerpetakan = laporan.sni_tidak_terpetakan
+                .map(b => `- baris ${b.nomor_baris}: ${b.baris}`);
+
+            let pesan = "✅ Data berhasil diekstrak dan dimasukkan ke form!";
+            if (perluCek.length) pesan += "\n\n⚠️ Perlu dicek manual:\n" + perluCek.join("\n");
+            if (tidakTerpetakan.length) pesan += "\n\n❓ Baris SNI tidak terpetakan:\n" + tidakTerpetakan.join("\n");
+            alert(pesan);
         }
 
     } catch (error) {
@@ -54,6 +77,13 @@ async function handlePdfUpload() {
     }
 }
 
+/**
+ * Helper: "yyyy-mm-dd" <-> "dd-mm-yyyy" (format simpan ISO vs format tampilan form)
+ */
+function balikTanggal(tanggal) {
+    return tanggal ? tanggal.split('-').reverse().join('-') : tanggal;
+}
+
 /**
  * Helper: Memetakan Data Rust ke Input HTML
  */
@@ -76,10 +106,23 @@ function fillForm(data) {
     // --- A. MAPPING METADATA (HEADER SURAT) ---
     // Menggunakan key snake_case sesuai output Rust
     setVal('namaPos', data.nama_pos);
+    setVal('das', data.das);
+    setVal('wilayahSungai', data.wilayah_sungai);
+    setVal('provinsi', data.provinsi);
+    setVal('kabupaten', data.kabupaten);
+    setVal('kecamatan', data.kecamatan);
+    setVal('desa', data.desa);
+    setVal('sungai', data.sungai);
+    setVal('elevasiPos', data.elevasi_pos);
+    setVal('laboratorium', data.laboratorium);
+    setVal('pelaksana', data.pelaksana);
+    setVal('tahun', data.tahun);
     setVal('koordinatGeografis', data.koordinat_geografis);
+    setVal('kelasBakuMutu', data.kelas_baku_mutu);
     
-    // Khusus Tanggal: Format dari Rust sudah "dd-mm-yyyy" yang cocok dengan Flatpickr
-    setVal('sampleDate', data.tanggal_sampling);
+    // Khusus Tanggal: Rust mengirim ISO "yyyy-mm-dd" / "HH:MM:SS", Flatpickr memakai "dd-mm-yyyy" / "HH:MM"
+    setVal('sampleDate', balikTanggal(data.tanggal_sampling));
+    setVal('sampleTime', data.waktu_sampling ? data.waktu_sampling.slice(0, 5) : null);
 
 
     // --- B. MAPPING PARAMETER KUALITAS AIR ---
@@ -119,6 +162,10 @@ function fillForm(data) {
 
     // Mikrobiologi
     setVal('totalColiform', data.totalColiform);
+
+    // Nilai tersensor ditampilkan dengan kualifiernya, misal "<0.002"
+    const inputId = { minyak_dan_lemak: 'minyakDanLemak', total_coliform: 'totalColiform' };
+    (data.tersensor || []).forEach(t => setVal(inputId[t.parameter] || t.parameter, `${t.kualifier}${t.batas_deteksi}`));
 }
 
 // --- FUNGSI STANDAR LAINNYA ---
@@ -129,33 +176,71 @@ function getFormData() {
         if (!el) return null;
         const val = el.value.trim();
         if (val === "") return null;
-        const stringFields = ['namaPos', 'das', 'wilayahSungai', 'provinsi', 'kabupaten', 'elevasiPos', 'pelaksana', 'kecamatan', 'laboratorium', 'sungai', 'desa', 'koordinatGeografis', 'sampleDate', 'sampleTime', 'statusIP', 'statusStoret'];
+        const stringFields = ['namaPos', 'das', 'wilayahSungai', 'provinsi', 'kabupaten', 'elevasiPos', 'pelaksana', 'kecamatan', 'laboratorium', 'sungai', 'desa', 'koordinatGeografis', 'kelasBakuMutu', 'sampleDate', 'sampleTime', 'statusIP', 'kelasIP', 'statusStoret'];
         if (stringFields.includes(id)) return val;
-        const num = parseFloat(val.replace(',', '.'));
+        // Nilai tersensor ("<0,002" / ">2400"): angka = batas deteksi, kualifier dikirim di `tersensor`
+        const num = parseFloat(val.replace(/^[<>]\s*/, '').replace(',', '.'));
         return isNaN(num) ? null : num;
     };
+
+    // Kumpulkan input parameter yang diawali "<" atau ">"
+    const inputId = { minyak_dan_lemak: 'minyakDanLemak', total_coliform: 'totalColiform' };
+    const tersensor = [];
+    document.querySelectorAll('.table-input-hasil').forEach(el => {
+        const match = el.value.trim().match(/^([<>])\s*([\d.,]+)$/);
+        if (!match) return;
+        const parameter = Object.keys(inputId).find(k => inputId[k] === el.id) || el.id;
+        tersensor.push({ parameter, kualifier: match[1], batas_deteksi: parseFloat(match[2].replace(',', '.')) });
+    });
+
     return {
-        nama_pos: getVal('namaPos'), das: getVal('das'), wilayah_sungai: getVal('wilayahSungai'), provinsi: getVal('provinsi'), kabupaten: getVal('kabupaten'), tahun: getVal('tahun'), elevasi_pos: getVal('elevasiPos'), pelaksana: getVal('pelaksana'), kecamatan: getVal('kecamatan'), laboratorium: getVal('laboratorium'), sungai: getVal('sungai'), desa: getVal('desa'), koordinat_geografis: getVal('koordinatGeografis'),
-        tanggal_sampling: getVal('sampleDate'), waktu_sampling: getVal('sampleTime'),
+        tersensor,
+        nama_pos: getVal('namaPos'), das: getVal('das'), wilayah_sungai: getVal('wilayahSungai'), provinsi: getVal('provinsi'), kabupaten: getVal('kabupaten'), tahun: getVal('tahun'), elevasi_pos: getVal('elevasiPos'), pelaksana: getVal('pelaksana'), kecamatan: getVal('kecamatan'), laboratorium: getVal('laboratorium'), sungai: getVal('sungai'), desa: getVal('desa'), koordinat_geografis: getVal('koordinatGeografis'), kelas_baku_mutu: getVal('kelasBakuMutu'),
+        tanggal_sampling: balikTanggal(getVal('sampleDate')), waktu_sampling: getVal('sampleTime'),
         temperatur: getVal('temperatur'), konduktivitas: getVal('konduktivitas'), kekeruhan: getVal('kekeruhan'), oksigen: getVal('oksigen'), ph: getVal('ph'), tds: getVal('tds'), tss: getVal('tss'), warna: getVal('warna'), klorida: getVal('klorida'), amoniak: getVal('amoniak'), nitrat: getVal('nitrat'), nitrit: getVal('nitrit'), fosfat: getVal('fosfat'), deterjen: getVal('deterjen'), arsen: getVal('arsen'), besi: getVal('besi'), mangan: getVal('mangan'), tembaga: getVal('tembaga'), merkuri: getVal('merkuri'), sianida: getVal('sianida'), fluorida: getVal('fluorida'), belerang: getVal('belerang'), cod: getVal('cod'), bod: getVal('bod'), fenol: getVal('fenol'), debit: getVal('debit'), minyakDanLemak: getVal('minyakDanLemak'), totalColiform: getVal('totalColiform'),
-        nilaiIp: getVal('metodeIndeksPencemaran'), statusIp: getVal('statusIP'), nilaiStoret: getVal('metodeStoret'), statusStoret: getVal('statusStoret')
+        nilaiIp: getVal('metodeIndeksPencemaran'), statusIp: getVal('statusIP'), kelasIp: getVal('kelasIP'), nilaiStoret: getVal('metodeStoret'), statusStoret: getVal('statusStoret')
     };
 }
 
 async function calculateIP() {
     const payload = getFormData();
     try {
-        const [score, status] = await invoke('calculate_ip_preview', { data: payload });
+        const [hasil, kelas] = await invoke('calculate_ip_preview', { data: payload, kelas: payload.kelas_baku_mutu });
+        const { nilai_ip: score, status } = hasil;
         const inputNilai = document.getElementById('metodeIndeksPencemaran');
         const inputStatus = document.getElementById('statusIP');
+        const inputKelas = document.getElementById('kelasIP');
         if (inputNilai) inputNilai.value = score;
         if (inputStatus) inputStatus.value = status;
+        if (inputKelas) inputKelas.value = kelas;
         if (status.includes("Baik") || status.includes("Memenuhi")) inputStatus.style.color = "green";
         else if (status.includes("Ringan")) inputStatus.style.color = "#f39c12"; 
         else inputStatus.style.color = "red";
+        highlightParameter(hasil.parameter);
     } catch (error) { console.error(error); alert("❌ Gagal: " + error); }
 }
 
+/**
+ * Helper: Tandai input parameter yang melampaui baku mutu (rincian dari Rust)
+ */
+function highlightParameter(rincian) {
+    // Nama parameter Rust (snake_case) -> ID input HTML
+    const inputId = { minyak_dan_lemak: 'minyakDanLemak', total_coliform: 'totalColiform' };
+
+    document.querySelectorAll('.table-input-hasil').forEach(el => {
+        el.style.backgroundColor = "";
+        el.title = "";
+    });
+
+    rincian.forEach(r => {
+        const el = document.getElementById(inputId[r.parameter] || r.parameter);
+        if (el && r.melampaui) {
+            el.style.backgroundColor = "#f8d7da";
+            el.title = `Ci/Lij = ${r.rasio.toFixed(2)} (melampaui baku mutu)`;
+        }
+    });
+}
+
 async function saveData() {
     const payload = getFormData();
     console.log("📦 [JS] Payload yang akan dikirim:", payload); // Debug 2